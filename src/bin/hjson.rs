@@ -0,0 +1,278 @@
+//! The `hjson` command line tool, to check Hjson files and convert
+//! them from and to JSON.
+//!
+//! It's built with the `cli` feature:
+//!
+//! ```bash
+//! cargo install deser-hjson --features cli
+//! ```
+use {
+    deser_hjson::{
+        from_str,
+        to_string_pretty,
+        transcode,
+        transcode_pretty,
+        Deserializer,
+        DeserializerOptions,
+        Error,
+        Path,
+        PathSegment,
+        Value,
+    },
+    std::{
+        env,
+        fs,
+        io::{self, Read},
+        process::ExitCode,
+    },
+};
+
+static USAGE: &str = "\
+Usage: hjson <command> [options] [file]
+
+Commands:
+    check [--json] [files]    check the files are valid Hjson (or strict
+                              JSON with --json) and print the errors
+    to-json [--pretty] [file] convert Hjson to JSON, keeping the numbers
+                              as written
+    from-json [file]          convert JSON to Hjson
+    fmt [--check] [file]      print the document formatted in a canonical
+                              way (comments aren't kept), or, with --check,
+                              only tell whether it's already formatted
+    get <path> [file]         print the value at a path (e.g. `servers[0].port`)
+
+The text is read from stdin when there's no file, or when the file is `-`.
+The exit code is 1 when the input is invalid, 2 for a wrong command.
+";
+
+/// Why a command failed
+enum Failure {
+    /// the command line is wrong
+    Usage(String),
+    /// the input can't be read, or is invalid: the message
+    /// is to be printed as is
+    Input(String),
+}
+
+type Outcome = Result<(), Failure>;
+
+/// A text to parse, with its origin
+struct Input {
+    name: String,
+    text: String,
+}
+
+impl Input {
+    /// read the file at `path`, or stdin when there's no path
+    /// or when it's `-`
+    fn read(path: Option<&str>) -> Result<Self, Failure> {
+        let read = match path {
+            None | Some("-") => {
+                let mut text = String::new();
+                io::stdin().read_to_string(&mut text).map(|_| ("<stdin>".to_string(), text))
+            }
+            Some(path) => fs::read_to_string(path).map(|text| (path.to_string(), text)),
+        };
+        match read {
+            Ok((name, text)) => Ok(Input { name, text }),
+            Err(e) => Err(Failure::Input(format!(
+                "error: can't read {}: {}\n",
+                path.unwrap_or("stdin"),
+                e,
+            ))),
+        }
+    }
+
+    /// describe a parsing error, with the relevant lines
+    fn describe(&self, err: &Error) -> String {
+        err.render(&self.text, Some(&self.name))
+    }
+
+    /// parse the text as Hjson
+    fn parse_hjson(&self) -> Result<Value, Failure> {
+        from_str(&self.text).map_err(|e| Failure::Input(self.describe(&e)))
+    }
+}
+
+/// The arguments of a command: its flags, checked against the
+/// allowed ones, and the other arguments
+struct Args<'a> {
+    flags: Vec<&'a str>,
+    positionals: Vec<&'a str>,
+}
+
+impl<'a> Args<'a> {
+    fn parse(args: &'a [String], allowed_flags: &[&str]) -> Result<Self, Failure> {
+        let mut flags = Vec::new();
+        let mut positionals = Vec::new();
+        for arg in args {
+            if arg.starts_with("--") {
+                if !allowed_flags.contains(&arg.as_str()) {
+                    return Err(Failure::Usage(format!("unexpected option: {}", arg)));
+                }
+                flags.push(arg.as_str());
+            } else {
+                positionals.push(arg.as_str());
+            }
+        }
+        Ok(Args { flags, positionals })
+    }
+    fn has(&self, flag: &str) -> bool {
+        self.flags.contains(&flag)
+    }
+    /// return the only file of the command, if any
+    fn file(&self, skip: usize) -> Result<Option<&'a str>, Failure> {
+        match &self.positionals[skip.min(self.positionals.len())..] {
+            [] => Ok(None),
+            [path] => Ok(Some(path)),
+            _ => Err(Failure::Usage("too many arguments".to_string())),
+        }
+    }
+}
+
+/// check files, reporting all the errors of each one
+fn check(args: &[String]) -> Outcome {
+    let args = Args::parse(args, &["--json"])?;
+    let options = if args.has("--json") {
+        DeserializerOptions::strict_json()
+    } else {
+        DeserializerOptions::default()
+    };
+    let paths = if args.positionals.is_empty() {
+        vec!["-"]
+    } else {
+        args.positionals.clone()
+    };
+    let mut report = String::new();
+    for path in paths {
+        let input = match Input::read(Some(path)) {
+            Ok(input) => input,
+            Err(Failure::Input(message)) => {
+                report.push_str(&message);
+                continue;
+            }
+            Err(failure) => return Err(failure),
+        };
+        let errors = Deserializer::with_options(&input.text, options.clone()).validate();
+        for err in &errors {
+            report.push_str(&input.describe(err));
+        }
+    }
+    if report.is_empty() {
+        Ok(())
+    } else {
+        Err(Failure::Input(report))
+    }
+}
+
+fn to_json(args: &[String]) -> Outcome {
+    let args = Args::parse(args, &["--pretty"])?;
+    let input = Input::read(args.file(0)?)?;
+    // the JSON is written only when the whole input is known to be valid
+    let mut json = Vec::new();
+    let transcoded = if args.has("--pretty") {
+        transcode_pretty(&input.text, &mut json)
+    } else {
+        transcode(&input.text, &mut json)
+    };
+    match transcoded {
+        Ok(()) => {
+            println!("{}", String::from_utf8_lossy(&json));
+            Ok(())
+        }
+        Err(e) => Err(Failure::Input(input.describe(&e))),
+    }
+}
+
+fn from_json(args: &[String]) -> Outcome {
+    let args = Args::parse(args, &[])?;
+    let input = Input::read(args.file(0)?)?;
+    let value: Value = serde_json::from_str(&input.text).map_err(|e| {
+        Failure::Input(format!("error: {}\n --> {}:{}:{}\n", e, input.name, e.line(), e.column()))
+    })?;
+    print_hjson(&value)
+}
+
+fn fmt(args: &[String]) -> Outcome {
+    let args = Args::parse(args, &["--check"])?;
+    let input = Input::read(args.file(0)?)?;
+    let value = input.parse_hjson()?;
+    if !args.has("--check") {
+        return print_hjson(&value);
+    }
+    let formatted = to_hjson(&value)?;
+    if formatted == input.text {
+        Ok(())
+    } else {
+        Err(Failure::Input(format!("{} isn't formatted\n", input.name)))
+    }
+}
+
+fn get(args: &[String]) -> Outcome {
+    let args = Args::parse(args, &[])?;
+    let path: Path = match args.positionals.first() {
+        Some(path) => path.parse()
+            .map_err(|e| Failure::Usage(format!("invalid path {:?}: {}", path, e)))?,
+        None => return Err(Failure::Usage("missing path".to_string())),
+    };
+    let value = Input::read(args.file(1)?)?.parse_hjson()?;
+    let value = path.iter().try_fold(&value, |value, segment| match segment {
+        PathSegment::Key(key) => value.get(key),
+        PathSegment::Index(idx) => value.get(*idx),
+    });
+    match value {
+        Some(Value::String(s)) => {
+            println!("{}", s);
+            Ok(())
+        }
+        Some(value) => print_hjson(value),
+        None => Err(Failure::Input(format!("error: no value at {}\n", path))),
+    }
+}
+
+/// format a value as Hjson, with a final newline
+fn to_hjson(value: &Value) -> Result<String, Failure> {
+    match to_string_pretty(value) {
+        Ok(hjson) => Ok(format!("{}\n", hjson.trim_end())),
+        Err(e) => Err(Failure::Input(format!("error: {}\n", e))),
+    }
+}
+
+fn print_hjson(value: &Value) -> Outcome {
+    print!("{}", to_hjson(value)?);
+    Ok(())
+}
+
+fn run(args: &[String]) -> Outcome {
+    let (command, args) = match args.split_first() {
+        Some((command, args)) => (command.as_str(), args),
+        None => return Err(Failure::Usage("missing command".to_string())),
+    };
+    match command {
+        "check" => check(args),
+        "to-json" => to_json(args),
+        "from-json" => from_json(args),
+        "fmt" => fmt(args),
+        "get" => get(args),
+        "help" | "-h" | "--help" => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(Failure::Usage(format!("unknown command: {}", command))),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Failure::Usage(message)) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            ExitCode::from(2)
+        }
+        Err(Failure::Input(message)) => {
+            eprint!("{}", message);
+            ExitCode::from(1)
+        }
+    }
+}
@@ -5,41 +5,173 @@ use {
         de_enum::*,
         de_map::*,
         de_number::*,
+        de_reader::*,
         de_seq::*,
+        de_spanned::*,
+        de_stream::*,
+        de_transcode::*,
+        de_validate::*,
         error::{
             Error,
             ErrorCode::{self, *},
             Result,
         },
+        options::*,
+        path::*,
+        spanned,
         utf8::*,
     },
     serde::de::{self, IntoDeserializer, Visitor},
+    std::{
+        borrow::Cow,
+        io::{self, BufRead},
+    },
 };
 
-/// The deserializer. You normally don't call it directly
-/// but use the `from_str` function available at crate's level.
+/// How many bytes after the current position are kept available
+/// when reading from a reader
+const READ_AHEAD: usize = 64;
+
+/// When reading from a reader, how many parsed bytes may be kept
+/// before they're dropped
+const MAX_PARSED_TEXT: usize = 64 * 1024;
+
+/// The text to deserialize
+enum Source<'de> {
+    /// the whole text, from which strings can be borrowed
+    Str(&'de str),
+    /// a reader, from which the text is read chunk after chunk
+    Reader(ChunkReader<'de>),
+}
+
+/// An element of the path to the value being deserialized,
+/// kept cheap to build as it's only used in errors
+#[derive(Debug, Clone)]
+enum PathItem {
+    /// a map key, as a range in the source
+    Key { start: usize, end: usize },
+    /// a map key whose text was dropped from the source
+    OwnedKey(String),
+    /// an array index
+    Index(usize),
+}
+
+/// The deserializer.
+///
+/// You normally don't call it directly but use the `from_str`
+/// function available at crate's level.
+///
+/// It's useful when you need more control, for example to
+/// deserialize with a `DeserializeSeed`, or to decide when to
+/// check there's nothing after the value:
+///
+/// ```
+/// use {
+///     deser_hjson::Deserializer,
+///     serde::Deserialize,
+/// };
+///
+/// let mut deserializer = Deserializer::from_str("[1, 2, 3]");
+/// let v = Vec::<u8>::deserialize(&mut deserializer).unwrap();
+/// deserializer.check_all_consumed().unwrap();
+/// assert_eq!(v, vec![1, 2, 3]);
+/// ```
 pub struct Deserializer<'de> {
-    // the complete string we received
-    src: &'de str,
+    // the complete string we received, or the
+    // part of the reader's text we still need
+    src: Source<'de>,
+
+    // the parsing options
+    options: DeserializerOptions,
 
-    // where we're at, in bytes
+    // where we're at, in bytes from the start of `text()`
     pos: usize,
 
-    // Make it possible to avoid reading a string as a quoteless
-    // string when a key map is waited for (for example in
-    //     {
-    //         key: value
-    //     }
-    // ) so that the key doesn't go til the end of the line.
+    // the position after which more text must be read, `usize::MAX`
+    // when there's nothing more to read (always for a string)
+    read_more_pos: usize,
+
+    // the map keys and array indexes leading to the current value
+    path: Vec<PathItem>,
+
+    // how many maps and arrays we're in
+    depth: usize,
+
+    // where the root value starts, after an eventual opening
+    // root delimiter line
+    root_start: usize,
+
+    // the position, line and col of the last map key whose location
+    // was computed, so that the next one is computed from there
+    key_cursor: (usize, usize, usize),
+
+    // the warnings raised while parsing, e.g. for duplicate keys
+    warnings: Vec<Error>,
+
+    // False while a map key is read (see `read_key`), so that a
+    // quoteless key stops at the colon instead of going til the end
+    // of the line, and true everywhere else, so that a quoteless
+    // value may contain colons (e.g. `url: http://host:8080/`).
     pub(crate) accept_quoteless_value: bool,
 }
 
 impl<'de> Deserializer<'de> {
 
+    /// build a deserializer with the default options
+    #[allow(clippy::should_implement_trait)] // FromStr can't borrow the source
     pub fn from_str(src: &'de str) -> Self {
+        Self::with_options(src, DeserializerOptions::default())
+    }
+
+    /// build a deserializer with specific options
+    pub fn with_options(src: &'de str, options: DeserializerOptions) -> Self {
+        Self::with_source(Source::Str(src), options)
+    }
+
+    /// build a deserializer reading its input from a reader, chunk after
+    /// chunk, instead of needing the whole text in memory.
+    ///
+    /// Nothing can be borrowed from the input. An error of the reader, IO
+    /// error or invalid UTF8, ends the input and is then returned by
+    /// `check_all_consumed`.
+    ///
+    /// ```
+    /// use {
+    ///     deser_hjson::Deserializer,
+    ///     serde::Deserialize,
+    /// };
+    ///
+    /// let hjson = "[\n  1\n  2\n]".as_bytes();
+    /// let mut deserializer = Deserializer::from_reader(hjson);
+    /// let v = Vec::<u8>::deserialize(&mut deserializer).unwrap();
+    /// deserializer.check_all_consumed().unwrap();
+    /// assert_eq!(v, vec![1, 2]);
+    /// ```
+    pub fn from_reader<R: BufRead + 'de>(reader: R) -> Self {
+        Self::reader_with_options(reader, DeserializerOptions::default())
+    }
+
+    /// build a deserializer reading from a reader, with specific options
+    pub fn reader_with_options<R: BufRead + 'de>(
+        reader: R,
+        options: DeserializerOptions,
+    ) -> Self {
+        let mut de = Self::with_source(Source::Reader(ChunkReader::new(reader)), options);
+        de.read_more();
+        de
+    }
+
+    fn with_source(src: Source<'de>, options: DeserializerOptions) -> Self {
         Deserializer {
             src,
+            options,
             pos: 0,
+            read_more_pos: usize::MAX,
+            path: Vec::new(),
+            depth: 0,
+            root_start: 0,
+            key_cursor: (0, 1, 1),
+            warnings: Vec::new(),
             accept_quoteless_value: true,
         }
     }
@@ -48,8 +180,51 @@ impl<'de> Deserializer<'de> {
     /// First line and first col are of index 1.
     #[cold]
     fn location(&self) -> (usize, usize) {
-        let (mut line, mut col) = (1, 1);
-        for ch in self.src[..self.pos].chars() {
+        self.location_at(self.pos())
+    }
+
+    /// Compute the number of lines and columns to the given pos.
+    fn location_at(&self, pos: usize) -> (usize, usize) {
+        let (line, col) = self.text_location();
+        self.location_from(self.offset(), line, col, pos)
+    }
+
+    /// the line and col of the start of `text()`
+    fn text_location(&self) -> (usize, usize) {
+        match &self.src {
+            Source::Str(_) => (1, 1),
+            Source::Reader(reader) => (reader.line, reader.col),
+        }
+    }
+
+    /// Compute the location of a map key, from the one of the previous
+    /// key, so that the whole text isn't scanned for each key
+    pub(crate) fn key_location(&mut self, pos: usize) -> (usize, usize) {
+        let (known_pos, line, col) = self.key_cursor;
+        let (line, col) = if known_pos <= pos {
+            self.location_from(known_pos, line, col, pos)
+        } else {
+            self.location_at(pos)
+        };
+        self.key_cursor = (pos, line, col);
+        (line, col)
+    }
+
+    /// Compute the number of lines and columns to the given pos, knowing
+    /// the ones of a previous pos, so that the text before isn't scanned
+    /// again (the previous pos may have been dropped)
+    fn location_from(
+        &self,
+        known_pos: usize,
+        mut line: usize,
+        mut col: usize,
+        pos: usize,
+    ) -> (usize, usize) {
+        if known_pos < self.offset() {
+            return self.location_at(pos);
+        }
+        let offset = self.offset();
+        for ch in self.text()[known_pos - offset..pos - offset].chars() {
             if ch == '\n' {
                 col = 1;
                 line += 1;
@@ -60,19 +235,13 @@ impl<'de> Deserializer<'de> {
         (line, col)
     }
 
+    /// the column of the current position, starting at 1
     fn col(&self) -> usize {
-        let mut p = self.pos;
-        loop {
-            if p == 0 {
-                break;
-            }
-            let b = self.src.as_bytes()[p];
-            if b == b'\r' || b == b'\n' {
-                break;
-            }
-            p -= 1;
+        match self.text()[..self.pos].rfind(['\r', '\n']) {
+            Some(idx) => self.pos - idx,
+            // the line starts before the text
+            None => self.text_location().1 + self.pos,
         }
-        self.pos - p
     }
 
     /// build a syntax error
@@ -86,6 +255,7 @@ impl<'de> Deserializer<'de> {
             col,
             code,
             at,
+            path: self.current_path(),
         }
     }
 
@@ -101,40 +271,347 @@ impl<'de> Deserializer<'de> {
                     line,
                     col,
                     message,
+                    path: self.current_path(),
                 })
             }
             e => Err(e),
         }
     }
 
+    /// enter a map value whose key starts at `key_start` and
+    /// was just read
+    #[inline]
+    pub(crate) fn push_key(&mut self, key_start: usize) {
+        let key_end = self.value_end(key_start);
+        self.path.push(PathItem::Key { start: key_start, end: key_end });
+    }
+
+    /// enter an array item
+    #[inline]
+    pub(crate) fn push_index(&mut self, idx: usize) {
+        self.path.push(PathItem::Index(idx));
+    }
+
+    /// leave the current map value or array item
+    #[inline]
+    pub(crate) fn pop_path(&mut self) {
+        self.path.pop();
+    }
+
+    /// build the path to the current value (this is costly
+    /// as keys must be unquoted and copied)
+    #[cold]
+    fn current_path(&self) -> Path {
+        let segments: Vec<PathSegment> = self.path.iter()
+            .map(|item| match item {
+                PathItem::Key { start, end } => {
+                    let offset = self.offset();
+                    PathSegment::Key(unquote_key(&self.text()[start - offset..end - offset]))
+                }
+                PathItem::OwnedKey(key) => PathSegment::Key(unquote_key(key)),
+                PathItem::Index(idx) => PathSegment::Index(*idx),
+            })
+            .collect();
+        segments.into()
+    }
+
+    /// when reading from a reader, drop the part of the text before the
+    /// current line, or before the current position when the line is
+    /// long, if it's big, as it won't be needed anymore.
+    ///
+    /// This must only be called between map entries or array items,
+    /// when no position in the dropped part is kept.
+    pub(crate) fn drop_parsed_text(&mut self) {
+        let reader = match &mut self.src {
+            Source::Reader(reader) if self.pos > MAX_PARSED_TEXT => reader,
+            _ => return,
+        };
+        // we keep the newline before the current line, unless it's far
+        let len = match reader.text[..self.pos].rfind('\n') {
+            Some(len) if self.pos - len <= MAX_PARSED_TEXT => len,
+            _ => self.pos,
+        };
+        let end = reader.offset + len;
+        for item in &mut self.path {
+            if let PathItem::Key { start, end: key_end } = *item {
+                if start < end {
+                    let offset = reader.offset;
+                    let key = reader.text[start - offset..key_end - offset].to_string();
+                    *item = PathItem::OwnedKey(key);
+                }
+            }
+        }
+        reader.drop_start(len);
+        self.pos -= len;
+        if self.read_more_pos != usize::MAX {
+            self.read_more_pos -= len;
+        }
+    }
+
+    /// take the error which stopped the reading, if any
+    pub(crate) fn take_read_error(&mut self) -> Option<Error> {
+        match &mut self.src {
+            Source::Reader(reader) => reader.error.take(),
+            Source::Str(_) => None,
+        }
+    }
+
     #[cold]
     pub(crate) fn fail<T>(&self, code: ErrorCode) -> Result<T> {
         Err(self.err(code))
     }
 
     /// return an error if there's more than just spaces
-    /// and comments in the remaining input
+    /// and comments in the remaining input (unless the
+    /// options say to ignore trailing characters)
     pub fn check_all_consumed(&mut self) -> Result<()> {
-        self.eat_shit().ok();
-        if self.input().is_empty() {
+        let eaten = self.eat_shit();
+        if let Some(err) = self.take_read_error() {
+            return Err(err);
+        }
+        if self.input().is_empty() || self.options.trailing_chars == TrailingChars::Ignore {
             Ok(())
+        } else if let Err(e @ Error::Syntax { code: Disallowed(_) | Eof, .. }) = eaten {
+            // a disallowed comment, or an unclosed one
+            Err(e)
         } else {
             self.fail(TrailingCharacters)
         }
     }
 
+    /// check the whole document, going on after syntax errors,
+    /// and return all the errors found (an empty vec meaning the
+    /// document is valid Hjson)
+    ///
+    /// ```
+    /// use deser_hjson::Deserializer;
+    ///
+    /// let errors = Deserializer::from_str("{\n a: 1\n b 2\n c: [3, :]\n}").validate();
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors[0].location(), Some((3, 4)));
+    /// assert_eq!(errors[1].location(), Some((4, 9)));
+    /// ```
+    pub fn validate(&mut self) -> Vec<Error> {
+        Validator::new(self).validate()
+    }
+
+    /// tell whether the current line is the root delimiter
+    /// and there's nothing before it on the line
+    pub(crate) fn is_at_root_delimiter(&mut self) -> bool {
+        if self.options.root_delimiter.is_none() {
+            return false;
+        }
+        self.read_line_end();
+        let line_start = match self.text()[..self.pos].rfind('\n') {
+            Some(idx) => idx + 1,
+            None if self.text_location().1 == 1 => 0,
+            None => return false, // the start of the line was dropped
+        };
+        let line_end = self.input().find('\n').map_or(self.text().len(), |idx| self.pos + idx);
+        self.options.root_delimiter.as_deref() == Some(self.text()[line_start..line_end].trim())
+    }
+
+    /// if the current line is the root delimiter, consume it
+    /// and return true
+    fn skip_root_delimiter(&mut self) -> bool {
+        if !self.is_at_root_delimiter() {
+            return false;
+        }
+        let len = self.input().find('\n').map_or(self.input().len(), |idx| idx + 1);
+        self.advance(len);
+        true
+    }
+
+    /// skip the root delimiter line if the text starts with it, as
+    /// is usual for front matter
+    pub(crate) fn skip_opening_root_delimiter(&mut self) {
+        let pos = self.pos;
+        if self.eat_shit().is_ok() && self.skip_root_delimiter() {
+            self.root_start = self.pos();
+        } else {
+            self.pos = pos;
+        }
+    }
+
+    /// return the position of the end of the root value which was just
+    /// read, or of the end of the root delimiter line following it
+    pub(crate) fn prefix_end(&mut self) -> usize {
+        let end = self.value_end(self.root_start);
+        let pos = self.pos;
+        if self.eat_shit().is_ok() && self.skip_root_delimiter() {
+            self.pos()
+        } else {
+            self.pos = pos;
+            end
+        }
+    }
+
+    /// write the value as compact JSON into the writer, without
+    /// building an intermediate value (see [transcode](crate::transcode))
+    pub fn transcode<W: io::Write>(&mut self, writer: W) -> Result<()> {
+        Transcoder::new(writer, false).value(self)
+    }
+
+    /// write the value as pretty JSON into the writer, indented
+    /// with two spaces
+    pub fn transcode_pretty<W: io::Write>(&mut self, writer: W) -> Result<()> {
+        Transcoder::new(writer, true).value(self)
+    }
+
+    /// turn the deserializer into an iterator over the values
+    /// following each other in the text (see [StreamDeserializer])
+    #[allow(clippy::should_implement_trait)] // the type of the items is chosen here
+    pub fn into_iter<T>(mut self) -> StreamDeserializer<'de, T>
+    where
+        T: de::Deserialize<'de>,
+    {
+        // a braceless map would take all the values
+        self.options.braceless_root = false;
+        StreamDeserializer::new(self)
+    }
+
+    /// the warnings raised while parsing, for example for duplicate keys
+    /// when the options say [DuplicateKeys::Warn]
+    ///
+    /// ```
+    /// use {
+    ///     deser_hjson::*,
+    ///     serde::Deserialize,
+    ///     std::collections::HashMap,
+    /// };
+    ///
+    /// let options = DeserializerOptions::new().duplicate_keys(DuplicateKeys::Warn);
+    /// let mut deserializer = Deserializer::with_options("a: 1\nb: 2\na: 3", options);
+    /// let map = HashMap::<String, u8>::deserialize(&mut deserializer).unwrap();
+    /// assert_eq!(map["a"], 3);
+    /// let warnings = deserializer.warnings();
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(warnings[0].location(), Some((3, 1)));
+    /// ```
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    pub(crate) fn warn(&mut self, warning: Error) {
+        self.warnings.push(warning);
+    }
+
+    /// go back to a position of the current text, for example
+    /// to read a map key again
+    pub(crate) fn rewind(&mut self, pos: usize) {
+        self.pos = pos - self.offset();
+    }
+
+    pub(crate) fn options(&self) -> &DeserializerOptions {
+        &self.options
+    }
+
+    /// the current position in the source, in bytes
+    #[inline(always)]
+    pub(crate) fn pos(&self) -> usize {
+        self.offset() + self.pos
+    }
+
+    /// the available text: the whole source, or the
+    /// lines read from the reader and not dropped
+    #[inline(always)]
+    fn text(&self) -> &str {
+        match &self.src {
+            Source::Str(src) => src,
+            Source::Reader(reader) => &reader.text,
+        }
+    }
+
+    /// the position in the source of the start of `text()`
+    #[inline(always)]
+    fn offset(&self) -> usize {
+        match &self.src {
+            Source::Str(_) => 0,
+            Source::Reader(reader) => reader.offset,
+        }
+    }
+
+    /// the part of `text()` between `start` and `end`, borrowed
+    /// from the source when it's a string
+    #[inline]
+    fn span(&self, start: usize, end: usize) -> Cow<'de, str> {
+        match &self.src {
+            Source::Str(src) => Cow::Borrowed(&src[start..end]),
+            Source::Reader(reader) => Cow::Owned(reader.text[start..end].to_string()),
+        }
+    }
+
+    /// when reading from a reader, read chunks until the text holds
+    /// `READ_AHEAD` bytes after the current position, or the end
+    #[inline(never)]
+    fn read_more(&mut self) {
+        while self.text().len() < self.pos + READ_AHEAD && self.read_chunk() {}
+    }
+
+    /// when reading from a reader, read the next chunk of text,
+    /// returning false when there's nothing more to read
+    fn read_chunk(&mut self) -> bool {
+        let reader = match &mut self.src {
+            Source::Reader(reader) => reader,
+            Source::Str(_) => return false,
+        };
+        let read = reader.read_chunk();
+        self.read_more_pos = if read {
+            reader.text.len().saturating_sub(READ_AHEAD)
+        } else {
+            usize::MAX
+        };
+        read
+    }
+
+    /// when reading from a reader, read until the text after the current
+    /// position holds a byte for which `stop` is true, followed by
+    /// `READ_AHEAD` bytes, or until the end
+    #[inline]
+    fn read_until(&mut self, stop: impl Fn(u8) -> bool) {
+        let mut from = self.pos;
+        while self.read_more_pos != usize::MAX {
+            let text = self.text().as_bytes();
+            if let Some(idx) = text[from..].iter().position(|&b| stop(b)) {
+                let end = from + idx + READ_AHEAD;
+                while self.text().len() < end && self.read_chunk() {}
+                return;
+            }
+            from = text.len();
+            self.read_chunk();
+        }
+    }
+
+    /// when reading from a reader, make sure the text
+    /// holds the rest of the current line
+    #[inline]
+    fn read_line_end(&mut self) {
+        self.read_until(|b| b == b'\n');
+    }
+
+    /// when reading from a reader, make sure the text holds the coming
+    /// token (e.g. a number or `true`), the spaces after it, and the
+    /// first char after them, which tells whether the token ends there
+    #[inline]
+    pub(crate) fn read_token(&mut self) {
+        self.read_until(|b| {
+            !(b.is_ascii_alphanumeric() || matches!(b, b'.' | b'+' | b'-' | b'_' | b' ' | b'\t'))
+        });
+    }
+
     /// what remains to be parsed (including the
     /// character we peeked at, if any)
     #[inline(always)]
-    pub(crate) fn input(&self) -> &'de str {
-        &self.src[self.pos..]
+    pub(crate) fn input(&self) -> &str {
+        &self.text()[self.pos..]
     }
 
     /// takes all remaining characters
     #[inline(always)]
-    pub(crate) fn take_all(&mut self) -> &'de str {
-        let s = &self.src[self.pos..];
-        self.pos = self.src.len();
+    pub(crate) fn take_all(&mut self) -> Cow<'de, str> {
+        let end = self.text().len();
+        let s = self.span(self.pos, end);
+        self.pos = end;
         s
     }
 
@@ -142,7 +619,7 @@ impl<'de> Deserializer<'de> {
     // adapted from https://doc.rust-lang.org/src/core/str/validations.rs.html
     #[inline]
     fn peek_code_point(&self) -> Result<(u32, usize)> {
-        let bytes = self.src.as_bytes();
+        let bytes = self.text().as_bytes();
         if self.pos >= bytes.len() {
             return self.fail(Eof);
         }
@@ -181,7 +658,7 @@ impl<'de> Deserializer<'de> {
     /// There's no guarantee the byte is a whole char
     #[inline]
     pub(crate) fn peek_byte(&self) -> Result<u8> {
-        let bytes = self.src.as_bytes();
+        let bytes = self.text().as_bytes();
         if self.pos >= bytes.len() {
             self.fail(Eof)
         } else {
@@ -193,13 +670,20 @@ impl<'de> Deserializer<'de> {
     /// caller MUST throw an error if the byte isn't a valid full character.
     #[inline]
     pub(crate) fn next_byte(&mut self) -> Result<u8> {
-        let bytes = self.src.as_bytes();
-        if self.pos >= bytes.len() {
-            self.fail(Eof)
+        let b = self.peek_byte()?;
+        self.advance(1);
+        Ok(b)
+    }
+
+    /// Consume the next byte if it's the expected one, or fail with
+    /// the given code (the error pointing at the unexpected byte)
+    #[inline]
+    pub(crate) fn expect_byte(&mut self, expected: u8, code: ErrorCode) -> Result<()> {
+        if self.peek_byte()? == expected {
+            self.advance(1);
+            Ok(())
         } else {
-            let b = bytes[self.pos];
-            self.pos += 1;
-            Ok(b)
+            self.fail(code)
         }
     }
 
@@ -214,47 +698,57 @@ impl<'de> Deserializer<'de> {
     #[inline]
     pub(crate) fn next_char(&mut self) -> Result<char> {
         let (code, len) = self.peek_code_point()?;
-        self.pos += len;
+        self.advance(len);
         let ch = unsafe { char::from_u32_unchecked(code) };
         Ok(ch)
     }
 
-    /// read bytes_count bytes of a string.
-    ///
-    /// The validity of pos + bytes_count as a valid UTF8 position must
-    /// have been checked before.
-    #[inline]
-    pub(crate) fn take_str(&mut self, bytes_count: usize) -> Result<&str> {
-        if self.src.len() >= self.pos + bytes_count {
-            let pos = self.pos;
-            self.pos += bytes_count;
-            Ok(&self.src[pos..pos + bytes_count])
-        } else {
-            self.fail(Eof)
-        }
-    }
-
     /// if the next bytes are s, then advance its length and return true
     /// otherwise return false.
     /// We do a comparison with a &[u8] to avoid the risk of trying read
     /// at arbitrary positions and fall between valid UTF8 positions
     #[inline]
     pub(crate) fn try_read(&mut self, s: &[u8]) -> bool {
-        #[allow(clippy::collapsible_if)]
-        if self.src.len() >= self.pos + s.len() {
-            if &self.src.as_bytes()[self.pos..self.pos + s.len()] == s {
-                self.pos += s.len();
-                return true;
-            }
+        if self.input().as_bytes().starts_with(s) {
+            self.advance(s.len());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// tell whether what's after the next `len` bytes on the line is only
+    /// spaces, a comment, or a separator (a comma or a closing bracket), in
+    /// which case these bytes are a literal (e.g. `true` or `3`) and not
+    /// the start of a quoteless string (e.g. `true story`).
+    /// A map key may also be followed by its colon.
+    pub(crate) fn is_literal_end(&self, len: usize) -> bool {
+        let rest = self.input()[len..].trim_start_matches([' ', '\t']);
+        rest.is_empty()
+            || (!self.accept_quoteless_value && rest.starts_with(':'))
+            || rest.starts_with(['\r', '\n', ',', ']', '}', '#'])
+            || rest.starts_with("//")
+            || rest.starts_with("/*")
+    }
+
+    /// if the next bytes are the literal `s` (e.g. `null`) and nothing
+    /// else on the line, then advance its length and return true
+    #[inline]
+    pub(crate) fn try_read_literal(&mut self, s: &[u8]) -> bool {
+        self.read_token();
+        if self.input().as_bytes().starts_with(s) && self.is_literal_end(s.len()) {
+            self.advance(s.len());
+            true
+        } else {
+            false
         }
-        false
     }
 
     /// return the `len` first bytes of the input, without checking anything
     /// (assuming it has been done) nor consuming anything
     #[inline]
-    pub(crate) fn start(&self, len: usize) -> &'de str {
-        &self.src[self.pos..self.pos + len]
+    pub(crate) fn start(&self, len: usize) -> Cow<'de, str> {
+        self.span(self.pos, self.pos + len)
     }
 
     /// remove the next character (which is assumed to be ch)
@@ -267,39 +761,51 @@ impl<'de> Deserializer<'de> {
     #[inline]
     pub(crate) fn advance(&mut self, bytes_count: usize) {
         self.pos += bytes_count;
+        if self.pos > self.read_more_pos {
+            self.read_more();
+        }
     }
 
     /// tells whether the next tree bytes are `'''` which
     /// is the start or end of a multiline string literal in Hjson
     #[inline]
     fn is_at_triple_quote(&self) -> bool {
-        self.src.len() >= self.pos + 3
-            && &self.src[self.pos..self.pos + 3] == "'''"
+        self.input().as_bytes().starts_with(b"'''")
     }
 
     #[inline]
     fn eat_line(&mut self) -> Result<()> {
-        self.accept_quoteless_value = true;
-        let bytes = self.src.as_bytes();
-        unsafe {
-            for i in self.pos..bytes.len() {
-                if *bytes.get_unchecked(i) == b'\n' {
-                    self.advance(i - self.pos + 1);
-                    return Ok(());
-                }
-            }
+        self.read_line_end();
+        if let Some(len) = self.input().find('\n') {
+            self.advance(len + 1);
+            return Ok(());
         }
+        // a comment may end the file without a newline
+        self.take_all();
         self.fail(Eof)
     }
 
+    /// tell whether the error is the end of the input with nothing
+    /// left, and not an unclosed block comment (whose text is left)
+    pub(crate) fn is_clean_eof(&self, e: &Error) -> bool {
+        e.is_eof() && self.input().is_empty()
+    }
+
     #[inline]
     pub(crate) fn eat_until_star_slash(&mut self) -> Result<()> {
-        match self.input().find("*/") {
-            Some(len) => {
+        let start = self.pos;
+        loop {
+            if let Some(len) = self.input().find("*/") {
                 self.advance(len + 2);
-                Ok(())
+                return Ok(());
+            }
+            // a '*' ending the text may be followed by a '/' not read yet
+            let input = self.input();
+            self.pos += input.len() - input.ends_with('*') as usize;
+            if !self.read_chunk() {
+                self.pos = start;
+                return self.fail(Eof);
             }
-            None => self.fail(Eof),
         }
     }
 
@@ -311,11 +817,13 @@ impl<'de> Deserializer<'de> {
         loop {
             match self.peek_byte()? {
                 b'#' => {
+                    self.check_comments_allowed(last_is_slash)?;
                     self.eat_line()?;
                     last_is_slash = false;
                 }
                 b'*' => {
                     if last_is_slash {
+                        self.check_comments_allowed(true)?;
                         self.eat_until_star_slash()?;
                     } else {
                         self.advance(1);
@@ -324,6 +832,7 @@ impl<'de> Deserializer<'de> {
                 }
                 b'/' => {
                     if last_is_slash {
+                        self.check_comments_allowed(true)?;
                         self.eat_line()?;
                         last_is_slash = false;
                     } else {
@@ -332,7 +841,6 @@ impl<'de> Deserializer<'de> {
                     }
                 }
                 b'\n' => {
-                    self.accept_quoteless_value = true;
                     self.advance(1);
                     last_is_slash = false;
                 }
@@ -351,17 +859,67 @@ impl<'de> Deserializer<'de> {
         }
     }
 
+    /// fail if comments are disallowed, pointing to the start
+    /// of the comment
+    #[inline]
+    fn check_comments_allowed(&mut self, after_slash: bool) -> Result<()> {
+        if self.options.comments {
+            return Ok(());
+        }
+        if after_slash {
+            self.pos -= 1;
+        }
+        self.fail(Disallowed(HjsonFeature::Comments))
+    }
+
+    /// eat the spaces and comments following a map value or an array
+    /// item, and the comma which may follow them, checking the options
+    /// allow the separator.
+    ///
+    /// `closer` is the char ending the map or array, `None` for the
+    /// braceless root map which ends with the input or a root delimiter.
+    pub(crate) fn eat_separator(&mut self, closer: Option<u8>) -> Result<()> {
+        if self.options.omitted_commas && self.options.trailing_commas {
+            return self.eat_shit_and(Some(','));
+        }
+        self.eat_shit()?;
+        if self.peek_byte()? == b',' {
+            let comma = self.pos;
+            self.advance(1);
+            if !self.options.trailing_commas && self.is_at_container_end(closer)? {
+                self.pos = comma;
+                return self.fail(Disallowed(HjsonFeature::TrailingCommas));
+            }
+        } else if !self.options.omitted_commas && !self.is_at_container_end(closer)? {
+            return self.fail(Disallowed(HjsonFeature::OmittedCommas));
+        }
+        Ok(())
+    }
+
+    /// eat spaces and comments, then tell whether the map or
+    /// array ends, `closer` being as in `eat_separator`
+    fn is_at_container_end(&mut self, closer: Option<u8>) -> Result<bool> {
+        match (self.eat_shit(), closer) {
+            (Ok(()), Some(closer)) => Ok(self.peek_byte()? == closer),
+            (Ok(()), None) => Ok(self.is_at_root_delimiter()),
+            (Err(e), None) if self.is_clean_eof(&e) => Ok(true),
+            (Err(e), _) => Err(e),
+        }
+    }
+
     pub(crate) fn eat_shit_and(&mut self, mut including: Option<char>) -> Result<()> {
         let mut last_is_slash = false;
         loop {
             let ch = self.peek_char()?;
             match ch {
                 '#' => {
+                    self.check_comments_allowed(last_is_slash)?;
                     self.eat_line()?;
                     last_is_slash = false;
                 }
                 '*' => {
                     if last_is_slash {
+                        self.check_comments_allowed(true)?;
                         self.eat_until_star_slash()?;
                     } else {
                         self.advance(1);
@@ -370,6 +928,7 @@ impl<'de> Deserializer<'de> {
                 }
                 '/' => {
                     if last_is_slash {
+                        self.check_comments_allowed(true)?;
                         self.eat_line()?;
                         last_is_slash = false;
                     } else {
@@ -378,7 +937,6 @@ impl<'de> Deserializer<'de> {
                     }
                 }
                 '\n' => {
-                    self.accept_quoteless_value = true;
                     self.advance(1);
                     last_is_slash = false;
                 }
@@ -404,9 +962,9 @@ impl<'de> Deserializer<'de> {
     /// Parse the JSON identifier `true` or `false`.
     fn parse_bool(&mut self) -> Result<bool> {
         self.eat_shit()?;
-        if self.try_read(b"true") {
+        if self.try_read_literal(b"true") {
             Ok(true)
-        } else if self.try_read(b"false") {
+        } else if self.try_read_literal(b"false") {
             Ok(false)
         } else {
             self.fail(ExpectedBoolean)
@@ -416,12 +974,22 @@ impl<'de> Deserializer<'de> {
     /// read the characters of the coming integer, without parsing the
     /// resulting string
     #[inline]
-    fn read_integer(&mut self, unsigned: bool) -> Result<&'de str> {
+    fn read_integer(&mut self, unsigned: bool) -> Result<(Cow<'de, str>, u32)> {
         // parsing could be done in the same loop but then I would have
         // to handle overflow
         self.eat_shit()?;
-        let bytes = self.src.as_bytes();
-        for (idx, b) in bytes.iter().skip(self.pos).enumerate() {
+        self.read_token();
+        if self.options.extended_integers {
+            if let Some(integer) = ExtendedInteger::read(self.input().as_bytes()) {
+                if unsigned && integer.digits.starts_with('-') {
+                    return self.fail(ExpectedPositiveInteger);
+                }
+                self.advance(integer.len);
+                return Ok((Cow::Owned(integer.digits), integer.radix));
+            }
+        }
+        let mut len = 0;
+        for (idx, b) in self.input().bytes().enumerate() {
             match b {
                 b'-' if unsigned => {
                     return self.fail(ExpectedPositiveInteger);
@@ -431,47 +999,94 @@ impl<'de> Deserializer<'de> {
                 }
                 b'0'..=b'9' | b'-' => {
                     // if it's too long, this will be handled at conversion
+                    len += 1;
                 }
-                _ => {
-                    let s = self.start(idx);
-                    self.advance(idx); // we keep the last char
-                    return Ok(s);
-                }
+                _ => break,
             }
         }
-        Ok(self.take_all())
+        let s = self.start(len);
+        self.advance(len); // we keep the last char
+        Ok((s, 10))
     }
 
-    /// read the characters of the coming floating point number, without parsing
-    #[inline]
-    fn read_float(&mut self) -> Result<&'de str> {
+    /// read an integer and convert it, raising an error with the given
+    /// code when it doesn't fit the type
+    fn parse_integer<T: Integer>(&mut self, unsigned: bool, code: ErrorCode) -> Result<T> {
+        let (s, radix) = self.read_integer(unsigned)?;
+        T::from_str_radix(&s, radix).map_err(|_| self.err(code))
+    }
+
+    /// if the `non_finite_floats` option is set and the next value is
+    /// `NaN`, `Infinity` or `-Infinity`, alone on its line, read it
+    pub(crate) fn try_read_non_finite_float(&mut self) -> Result<Option<f64>> {
+        let float = self.peek_non_finite_float()?;
+        if let Some((_, len)) = float {
+            self.advance(len);
+        }
+        Ok(float.map(|(v, _)| v))
+    }
+
+    /// as `try_read_non_finite_float`, but without consuming the
+    /// value, whose length is returned
+    pub(crate) fn peek_non_finite_float(&mut self) -> Result<Option<(f64, usize)>> {
+        if !self.options.non_finite_floats {
+            return Ok(None);
+        }
         self.eat_shit()?;
-        let bytes = &self.src.as_bytes()[self.pos..];
-        for (idx, b) in bytes.iter().enumerate() {
-            match b {
-                b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E' => {
-                    // if it's invalid, this will be handled at conversion
-                }
-                _ => {
-                    let s = self.start(idx);
-                    self.advance(idx); // we keep the last char
-                    return Ok(s);
-                }
+        self.read_token();
+        for (literal, v) in [
+            ("NaN", f64::NAN),
+            ("Infinity", f64::INFINITY),
+            ("-Infinity", f64::NEG_INFINITY),
+        ] {
+            if self.input().starts_with(literal) && self.is_literal_end(literal.len()) {
+                return Ok(Some((v, literal.len())));
             }
         }
-        Ok(self.take_all())
+        Ok(None)
     }
 
-    /// Parse a string until the next unescaped quote
+    /// read the characters of the coming floating point number, without parsing
     #[inline]
-    fn parse_quoted_string(&mut self) -> Result<String> {
-        let mut s = String::new();
+    fn read_float(&mut self) -> Result<Cow<'de, str>> {
+        self.eat_shit()?;
+        self.read_token();
+        // if it's invalid, this will be handled at conversion
+        let len = self.input().bytes()
+            .take_while(|b| matches!(b, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'))
+            .count();
+        let s = self.start(len);
+        self.advance(len); // we keep the last char
+        Ok(s)
+    }
+
+    /// Parse a string until the next unescaped quote.
+    ///
+    /// The string is borrowed from the source when there's
+    /// no escape sequence.
+    #[inline]
+    fn parse_quoted_string(&mut self) -> Result<Cow<'de, str>> {
+        if !self.options.single_quotes && self.peek_byte()? == b'\'' {
+            return self.fail(Disallowed(HjsonFeature::SingleQuotes));
+        }
         let starting_quote = self.next_char()?;
+        let start = self.pos;
+        loop {
+            let c = self.next_char()?;
+            if c == starting_quote {
+                return Ok(self.span(start, self.pos - 1));
+            } else if c == '\\' {
+                self.pos -= 1; // the escape sequence will be read again
+                break;
+            }
+        }
+        let mut s = self.text()[start..self.pos].to_string();
         loop {
             let mut c = self.next_char()?;
             if c == starting_quote {
                 break;
             } else if c == '\\' {
+                let escape_start = self.pos - 1;
                 c = match self.next_byte()? {
                     b'\"' => '\"',
                     b'\'' => '\'',
@@ -482,36 +1097,80 @@ impl<'de> Deserializer<'de> {
                     b'n' => '\n',
                     b'r' => '\r',
                     b't' => '\t',
-                    b'u' => {
-                        self.take_str(4).ok()
-                            .and_then(|s| u32::from_str_radix(s, 16).ok())
-                            .and_then(std::char::from_u32)
-                            .ok_or_else(|| self.err(InvalidEscapeSequence))?
-                    }
+                    b'u' => self.parse_unicode_escape(escape_start)?,
                     _ => {
+                        self.pos = escape_start;
                         return self.fail(InvalidEscapeSequence);
                     }
                 };
             }
             s.push(c);
         }
-        Ok(s)
+        Ok(Cow::Owned(s))
     }
 
-    /// Parse a string until end of line
-    fn parse_quoteless_str(&mut self) -> Result<&'de str> {
-        for (idx, ch) in self.input().char_indices() {
-            if ch == '\r' || ch == '\n' {
-                let s = self.start(idx);
-                self.advance(idx + 1);
-                return Ok(s.trim_end());
+    /// read the 4 hex digits of a `\u` escape sequence, failing with an
+    /// error pointing to the start of the sequence
+    fn parse_hex4(&mut self, escape_start: usize) -> Result<u32> {
+        let hex = self.input().get(..4)
+            .filter(|s| s.bytes().all(|b| b.is_ascii_hexdigit()));
+        match hex.and_then(|s| u32::from_str_radix(s, 16).ok()) {
+            Some(code) => {
+                self.advance(4);
+                Ok(code)
+            }
+            None => {
+                self.pos = escape_start; // so that the error points to the '\\'
+                self.fail(InvalidEscapeSequence)
             }
         }
-        Ok(self.take_all().trim_end())
+    }
+
+    /// parse what follows `\u` in a quoted string: either a char of the
+    /// BMP or the high surrogate of an UTF-16 pair, which must then be
+    /// followed by the escaped low surrogate (e.g. `\ud83d\ude00`)
+    fn parse_unicode_escape(&mut self, escape_start: usize) -> Result<char> {
+        let code = match self.parse_hex4(escape_start)? {
+            high @ 0xD800..=0xDBFF => {
+                let low_start = self.pos;
+                let low = if self.try_read(b"\\u") {
+                    self.parse_hex4(low_start)?
+                } else {
+                    0
+                };
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    self.pos = escape_start;
+                    return self.fail(LoneSurrogate);
+                }
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => {
+                self.pos = escape_start;
+                return self.fail(LoneSurrogate);
+            }
+            code => code,
+        };
+        // all surrogates have been excluded so the code is a valid char
+        Ok(std::char::from_u32(code).unwrap())
+    }
+
+    /// Parse a string until end of line
+    fn parse_quoteless_str(&mut self) -> Result<Cow<'de, str>> {
+        self.read_line_end();
+        let (len, eol_len) = match self.input().find(['\r', '\n']) {
+            Some(idx) => (idx, 1),
+            None => (self.input().len(), 0),
+        };
+        let s = self.start(self.input()[..len].trim_end().len());
+        self.advance(len + eol_len);
+        Ok(s)
     }
 
     /// Parse a string until the next triple quote.
     fn parse_multiline_string(&mut self) -> Result<String> {
+        if !self.options.multiline_strings {
+            return self.fail(Disallowed(HjsonFeature::MultilineStrings));
+        }
         let indent = self.col() - 1;
         self.advance(3); // consume the triple quote
 
@@ -539,9 +1198,9 @@ impl<'de> Deserializer<'de> {
         let mut rem = indent; // the number of leading spaces we remove
         while let Ok(ch) = self.next_char() {
             match ch {
-                '\'' if self.src.as_bytes()[self.pos] == b'\'' && self.src.as_bytes()[self.pos+1] == b'\'' => {
+                '\'' if self.input().as_bytes().starts_with(b"''") => {
                     self.advance(2); // the 2 other quotes
-                    v.truncate(v.trim_end_matches(|c| c=='\n' || c=='\r').len()); // trimming \n at end
+                    v.truncate(v.trim_end_matches(['\n', '\r']).len()); // trimming \n at end
                     return Ok(v);
                 }
                 '\n' => {
@@ -572,108 +1231,242 @@ impl<'de> Deserializer<'de> {
     /// Parse an identifier without quotes:
     /// - map key
     /// - enum variant
-    fn parse_quoteless_identifier(&mut self) -> Result<&'de str> {
+    fn parse_quoteless_identifier(&mut self) -> Result<Cow<'de, str>> {
         self.eat_shit()?;
-        for (idx, ch) in self.input().char_indices() {
-            match ch {
-                ',' | '[' | ']' | '{' | '}' | ':' | '\r'| '\n' => {
-                    let s = self.start(idx);
-                    self.advance(idx);
-                    return Ok(s);
-                }
-                ' ' | '\t' => {
-                    let s = self.start(idx);
-                    self.advance(idx + 1);
-                    return Ok(s);
-                }
-                _ => {}
-            }
+        if !self.options.quoteless_keys {
+            return self.fail(Disallowed(HjsonFeature::QuotelessKeys));
         }
-        Ok(self.take_all())
+        self.read_until(|b| {
+            matches!(b, b',' | b'[' | b']' | b'{' | b'}' | b':' | b'\r' | b'\n' | b' ' | b'\t')
+        });
+        let (len, space_len) = match self.input().find([',', '[', ']', '{', '}', ':', '\r', '\n', ' ', '\t']) {
+            Some(idx) if matches!(self.input().as_bytes()[idx], b' ' | b'\t') => (idx, 1),
+            Some(idx) => (idx, 0),
+            None => (self.input().len(), 0),
+        };
+        let s = self.start(len);
+        self.advance(len + space_len);
+        Ok(s)
     }
 
     /// parse a string which may be a value
     /// (i.e. not an map key or variant identifier )
-    fn parse_string_value(&mut self) -> Result<String> {
+    pub(crate) fn parse_string_value(&mut self) -> Result<Cow<'de, str>> {
         self.eat_shit()?;
         let b = self.peek_byte()?;
-        let v = match b {
+        match b {
             b',' | b':' | b'[' | b']' | b'{' | b'}' => self.fail(UnexpectedChar),
-            b'\'' if self.is_at_triple_quote() => self.parse_multiline_string(),
+            b'\'' if self.is_at_triple_quote() => self.parse_multiline_string().map(Cow::Owned),
             b'"' | b'\'' => self.parse_quoted_string(),
-            _ => (if self.accept_quoteless_value {
+            _ => if !self.accept_quoteless_value {
+                self.parse_quoteless_identifier()
+            } else if self.options.quoteless_values {
                 self.parse_quoteless_str()
             } else {
-                self.parse_quoteless_identifier()
-            })
-            .map(|s| s.to_string()),
-        };
-        self.accept_quoteless_value = true;
-        v
+                self.fail(Disallowed(HjsonFeature::QuotelessValues))
+            },
+        }
     }
 
     #[inline]
-    fn parse_identifier(&mut self) -> Result<String> {
+    fn parse_identifier(&mut self) -> Result<Cow<'de, str>> {
         self.eat_shit()?;
         let b = self.peek_byte()?;
-        // we set accept_quoteless_value to true so that a quoteless
-        // string can be accepted *after* the current identifier
-        self.accept_quoteless_value = true;
-        let r = match b {
+        match b {
             b',' | b':' | b'[' | b']' | b'{' | b'}' => self.fail(UnexpectedChar),
             b'"' | b'\'' => self.parse_quoted_string(),
-            _ => self.parse_quoteless_identifier().map(|s| s.to_string())
-        };
+            _ => self.parse_quoteless_identifier(),
+        }
+    }
+
+    /// enter a map or an array, failing when the nesting is
+    /// deeper than allowed by the recursion limit
+    pub(crate) fn enter_nested(&mut self) -> Result<()> {
+        if self.options.recursion_limit.is_some_and(|limit| self.depth >= limit) {
+            return self.fail(RecursionLimitExceeded);
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// leave a map or an array entered with `enter_nested`
+    pub(crate) fn leave_nested(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// call `f` within a nested map or array
+    pub(crate) fn nested<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        self.enter_nested()?;
+        let r = f(self);
+        self.leave_nested();
         r
     }
 
+    /// read a map key (or the name of an enum variant) with the given
+    /// seed, then the colon following it.
+    ///
+    /// Whatever the deserialize method called by the seed, a quoteless
+    /// key stops before the colon, and the value after can contain colons
+    pub(crate) fn read_key<K>(&mut self, seed: K) -> Result<K::Value>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        if !self.options.quoteless_keys {
+            self.eat_shit()?;
+            if !matches!(self.peek_byte()?, b'"' | b'\'') {
+                return self.fail(Disallowed(HjsonFeature::QuotelessKeys));
+            }
+        }
+        self.accept_quoteless_value = false;
+        let key_start = self.pos();
+        let key = seed.deserialize(&mut *self);
+        self.accept_quoteless_value = true;
+        let key = key?;
+        self.push_key(key_start);
+        self.eat_shit()?;
+        self.expect_byte(b':', ExpectedMapColon)?;
+        Ok(key)
+    }
+
+    /// tell whether the input starts with a map key followed by a colon,
+    /// which is how we recognize a braceless map when the type of the
+    /// document isn't known
+    pub(crate) fn is_at_map_key(&mut self) -> bool {
+        self.read_line_end();
+        let input = self.input();
+        let after_key = match input.as_bytes().first() {
+            Some(&quote) if quote == b'"' || quote == b'\'' => {
+                let mut escaped = false;
+                input.char_indices().skip(1).find(|&(_, ch)| {
+                    if escaped {
+                        escaped = false;
+                        false
+                    } else if ch == '\\' {
+                        escaped = true;
+                        false
+                    } else {
+                        ch == quote as char
+                    }
+                }).map(|(idx, _)| &input[idx + 1..])
+            }
+            _ => input
+                .find([',', '[', ']', '{', '}', ':', '\r', '\n', ' ', '\t'])
+                .filter(|&idx| idx > 0)
+                .map(|idx| &input[idx..]),
+        };
+        after_key
+            .map(|s| s.trim_start_matches([' ', '\t']).starts_with(':'))
+            .unwrap_or(false)
+    }
+
+    /// return the position of the end of a value which started
+    /// at `start` and was just read, excluding the spaces and
+    /// newline which may have been consumed after it
+    pub(crate) fn value_end(&self, start: usize) -> usize {
+        // the start of a big value may have been dropped
+        let start = start.max(self.offset());
+        let value = &self.text()[start - self.offset()..self.pos];
+        start + value.trim_end().len()
+    }
+
+    /// deserialize a `Spanned`, giving its visitor the location of
+    /// the value before the value itself
+    fn deserialize_spanned<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        // we don't consume the spaces before the value: they may be
+        // significant (for example for a braceless map)
+        let pos = self.pos;
+        let start = match self.eat_shit() {
+            Ok(()) => self.pos(),
+            Err(_) => pos + self.offset(),
+        };
+        self.pos = pos;
+        let (line, col) = self.location_at(start);
+        visitor.visit_map(SpannedReader::new(self, start, line, col))
+    }
+
+    /// eat the spaces and comments before a value whose type isn't
+    /// known, and tell whether it's a braceless root map (an empty
+    /// document being one)
+    pub(crate) fn is_at_braceless_root(&mut self) -> Result<bool> {
+        // the first key of the braceless map is at the root position too
+        let at_root = self.pos() == self.root_start && self.accept_quoteless_value;
+        let on_start = at_root && self.options.braceless_root;
+        if let Err(e) = self.eat_shit() {
+            return if on_start && self.is_clean_eof(&e) {
+                Ok(true)
+            } else {
+                Err(e)
+            };
+        }
+        if on_start && self.is_at_map_key() {
+            return Ok(true);
+        }
+        if at_root && !self.options.quoteless_values && self.is_at_map_key() {
+            // it would otherwise be read as a quoteless string
+            return self.fail(Disallowed(HjsonFeature::BracelessRoot));
+        }
+        Ok(false)
+    }
+
     /// Braceless Hjson: same than usual but not within { and },
-    /// can only be for the whole document
+    /// can only be for the whole document.
+    ///
+    /// The braceless map counts as a nesting level, like a braced one
     fn deserialize_braceless_map<V>(&mut self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        let mut map_reader = MapReader::braceless(self);
-        map_reader.braceless = true;
-        let value = match visitor.visit_map(map_reader) {
-            Ok(v) => v,
-            Err(e) => {
-                return self.cook_err(e);
+        self.nested(|de| {
+            let mut map_reader = MapReader::braceless(de);
+            map_reader.braceless = true;
+            match visitor.visit_map(map_reader) {
+                Ok(v) => Ok(v),
+                Err(e) => de.cook_err(e),
             }
-        };
-        Ok(value)
+        })
     }
 
 }
 
-impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     type Error = Error;
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.eat_shit()?;
+        if self.is_at_braceless_root()? {
+            return self.deserialize_braceless_map(visitor);
+        }
+        if let Some(v) = self.try_read_non_finite_float()? {
+            return visitor.visit_f64(v);
+        }
         match self.peek_byte()? {
             b'"' | b'\'' => self.deserialize_string(visitor),
-            b'0'..=b'9' | b'-' => {
-                let number = Number::read(self)?;
-                number.visit(self, visitor)
-            }
+            b'0'..=b'9' | b'-' => match Number::try_read(self) {
+                Some(number) => number.visit(self, visitor),
+                None => visit_cow_str(self.parse_string_value()?, visitor),
+            },
             b'[' => self.deserialize_seq(visitor),
             b'{' => self.deserialize_map(visitor),
             _ => {
-                if self.try_read(b"null") {
+                if self.try_read_literal(b"null") {
                     return visitor.visit_none();
                 }
-                if self.try_read(b"true") {
+                if self.try_read_literal(b"true") {
                     return visitor.visit_bool(true);
                 }
-                if self.try_read(b"false") {
+                if self.try_read_literal(b"false") {
                     return visitor.visit_bool(false);
                 }
                 let s = self.parse_string_value()?;
-                visitor.visit_string(s)
+                visit_cow_str(s, visitor)
             }
         }
     }
@@ -689,9 +1482,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        let v = self
-            .read_integer(false)
-            .and_then(|s| s.parse().map_err(|_| self.err(ExpectedI8)))?;
+        let v = self.parse_integer(false, ExpectedI8)?;
         visitor.visit_i8(v)
     }
 
@@ -699,9 +1490,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        let v = self
-            .read_integer(false)
-            .and_then(|s| s.parse().map_err(|_| self.err(ExpectedI16)))?;
+        let v = self.parse_integer(false, ExpectedI16)?;
         visitor.visit_i16(v)
     }
 
@@ -709,9 +1498,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        let v = self
-            .read_integer(false)
-            .and_then(|s| s.parse().map_err(|_| self.err(ExpectedI32)))?;
+        let v = self.parse_integer(false, ExpectedI32)?;
         visitor.visit_i32(v)
     }
 
@@ -719,19 +1506,23 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        let v = self
-            .read_integer(false)
-            .and_then(|s| s.parse().map_err(|_| self.err(ExpectedI64)))?;
+        let v = self.parse_integer(false, ExpectedI64)?;
         visitor.visit_i64(v)
     }
 
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.parse_integer(false, ExpectedI128)?;
+        visitor.visit_i128(v)
+    }
+
     fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        let v = self
-            .read_integer(true)
-            .and_then(|s| s.parse().map_err(|_| self.err(ExpectedU8)))?;
+        let v = self.parse_integer(true, ExpectedU8)?;
         visitor.visit_u8(v)
     }
 
@@ -739,9 +1530,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        let v = self
-            .read_integer(true)
-            .and_then(|s| s.parse().map_err(|_| self.err(ExpectedU16)))?;
+        let v = self.parse_integer(true, ExpectedU16)?;
         visitor.visit_u16(v)
     }
 
@@ -749,9 +1538,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        let v = self
-            .read_integer(true)
-            .and_then(|s| s.parse().map_err(|_| self.err(ExpectedU32)))?;
+        let v = self.parse_integer(true, ExpectedU32)?;
         visitor.visit_u32(v)
     }
 
@@ -759,16 +1546,25 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        let v = self
-            .read_integer(true)
-            .and_then(|s| s.parse().map_err(|_| self.err(ExpectedU64)))?;
+        let v = self.parse_integer(true, ExpectedU64)?;
         visitor.visit_u64(v)
     }
 
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v = self.parse_integer(true, ExpectedU128)?;
+        visitor.visit_u128(v)
+    }
+
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if let Some(v) = self.try_read_non_finite_float()? {
+            return visitor.visit_f32(v as f32);
+        }
         let v = self
             .read_float()
             .and_then(|s| s.parse().map_err(|_| self.err(ExpectedF32)))?;
@@ -779,6 +1575,9 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
+        if let Some(v) = self.try_read_non_finite_float()? {
+            return visitor.visit_f64(v);
+        }
         let v = self
             .read_float()
             .and_then(|s| s.parse().map_err(|_| self.err(ExpectedF64)))?;
@@ -799,16 +1598,17 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        // we can't always borrow strs from the source as it's not possible
-        // when there's an escape sequence. So str are parsed as strings.
-        self.deserialize_string(visitor)
+        // strings are borrowed from the source when possible, which
+        // isn't the case when there's an escape sequence or when it's
+        // a multiline string
+        visit_cow_str(self.parse_string_value()?, visitor)
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_string(self.parse_string_value()?)
+        self.deserialize_str(visitor)
     }
 
     fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
@@ -830,7 +1630,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         V: Visitor<'de>,
     {
         self.eat_shit()?;
-        if self.try_read(b"null") {
+        if self.try_read_literal(b"null") {
             visitor.visit_none()
         } else {
             visitor.visit_some(self)
@@ -843,7 +1643,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         V: Visitor<'de>,
     {
         self.eat_shit()?;
-        if self.try_read(b"null") {
+        if self.try_read_literal(b"null") {
             visitor.visit_unit()
         } else {
             self.fail(ExpectedNull)
@@ -871,16 +1671,15 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         V: Visitor<'de>,
     {
         self.eat_shit()?;
-        if self.next_byte()? == b'[' {
-            let value = visitor.visit_seq(SeqReader::new(self))?;
-            if self.next_byte()? == b']' {
-                Ok(value)
-            } else {
-                self.fail(ExpectedArrayEnd)
-            }
-        } else {
-            self.fail(ExpectedArray)
+        if self.peek_byte()? != b'[' {
+            return self.fail(ExpectedArray);
         }
+        self.nested(|de| {
+            de.advance(1);
+            let value = visitor.visit_seq(SeqReader::new(de))?;
+            de.expect_byte(b']', ExpectedArrayEnd)?;
+            Ok(value)
+        })
     }
 
     fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
@@ -906,30 +1705,32 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        let on_start = self.pos == 0;
+        let at_root = self.pos() == self.root_start;
+        let on_start = at_root && self.options.braceless_root;
         if let Err(e) = self.eat_shit() {
-            if on_start && e.is_eof() {
+            if on_start && self.is_clean_eof(&e) {
                 return self.deserialize_braceless_map(visitor);
             } else {
                 return Err(e);
             }
         }
         if self.peek_byte()? == b'{' {
-            self.advance(1);
-            let value = match visitor.visit_map(MapReader::within_braces(self)) {
-                Ok(v) => v,
-                Err(e) => {
-                    return self.cook_err(e);
-                }
-            };
-            self.eat_shit()?;
-            if self.next_byte()? == b'}' {
+            self.nested(|de| {
+                de.advance(1);
+                let value = match visitor.visit_map(MapReader::within_braces(de)) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        return de.cook_err(e);
+                    }
+                };
+                de.eat_shit()?;
+                de.expect_byte(b'}', ExpectedMapEnd)?;
                 Ok(value)
-            } else {
-                self.fail(ExpectedMapEnd)
-            }
+            })
         } else if on_start {
             self.deserialize_braceless_map(visitor)
+        } else if at_root && self.is_at_map_key() {
+            self.fail(Disallowed(HjsonFeature::BracelessRoot))
         } else {
             self.fail(ExpectedMap)
         }
@@ -937,13 +1738,16 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
 
     fn deserialize_struct<V>(
         self,
-        _name: &'static str,
-        _fields: &'static [&'static str],
+        name: &'static str,
+        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if name == spanned::NAME && fields == spanned::FIELDS {
+            return self.deserialize_spanned(visitor);
+        }
         self.deserialize_map(visitor)
     }
 
@@ -960,19 +1764,17 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         match self.peek_byte()? {
             b'"' | b'\'' => {
                 // Visit a unit variant.
-                visitor.visit_enum(self.parse_quoted_string()?.into_deserializer())
+                visitor.visit_enum(self.parse_quoted_string()?.into_owned().into_deserializer())
             }
-            b'{' => {
-                self.advance(1);
+            b'{' => self.nested(|de| {
+                de.advance(1);
                 // Visit a newtype variant, tuple variant, or struct variant.
-                let value = visitor.visit_enum(EnumReader::new(self))?;
-                self.eat_shit()?;
-                if self.next_byte()? == b'}' {
-                    Ok(value)
-                } else {
-                    self.fail(ExpectedMapEnd)
-                }
-            }
+                let value = visitor.visit_enum(EnumReader::new(de))?;
+                de.pop_path();
+                de.eat_shit()?;
+                de.expect_byte(b'}', ExpectedMapEnd)?;
+                Ok(value)
+            }),
             _ => {
                 visitor.visit_enum(self.parse_quoteless_identifier()?.into_deserializer())
             }
@@ -983,7 +1785,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_string(self.parse_identifier()?)
+        visit_cow_str(self.parse_identifier()?, visitor)
     }
 
     fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
@@ -993,3 +1795,25 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         self.deserialize_any(visitor)
     }
 }
+
+/// the text of a map key, as written in the source
+fn unquote_key(key: &str) -> String {
+    if key.starts_with(['"', '\'']) {
+        Deserializer::from_str(key).parse_quoted_string()
+            .map(|s| s.into_owned())
+            .unwrap_or_else(|_| key.to_string())
+    } else {
+        key.to_string()
+    }
+}
+
+/// give the visitor a borrowed str when possible, an owned string otherwise
+fn visit_cow_str<'de, V>(s: Cow<'de, str>, visitor: V) -> Result<V::Value>
+where
+    V: Visitor<'de>,
+{
+    match s {
+        Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+        Cow::Owned(s) => visitor.visit_string(s),
+    }
+}
@@ -32,13 +32,9 @@ impl<'de, 'a> EnumAccess<'de> for EnumReader<'a, 'de> {
         // The `deserialize_enum` method parsed a `{` character so we are
         // currently inside of a map. The seed will be deserializing itself from
         // the key of the map.
-        let val = seed.deserialize(&mut *self.de)?;
         self.de.eat_shit()?;
-        if self.de.next_byte()? == b':' {
-            Ok((val, self))
-        } else {
-            self.de.fail(ExpectedMapColon)
-        }
+        let val = self.de.read_key(seed)?;
+        Ok((val, self))
     }
 }
 
@@ -1,23 +1,129 @@
 use {
     crate::{
         de::Deserializer,
-        error::{Error, ErrorCode::*, Result},
+        error::{Error, ErrorCode, Result},
+        options::DuplicateKeys,
+    },
+    serde::de::{DeserializeSeed, IgnoredAny, MapAccess},
+    std::{
+        collections::HashMap,
+        marker::PhantomData,
     },
-    serde::de::{DeserializeSeed, MapAccess},
 };
 
 pub struct MapReader<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
     /// if braceless is true, the map may be closed by an eof instead of a '}'
     pub braceless: bool,
+    /// the keys already read, with the line and col of their first
+    /// occurrence, when the policy needs them to be checked
+    seen_keys: Option<HashMap<String, (usize, usize)>>,
 }
 
 impl<'a, 'de> MapReader<'a, 'de> {
     pub fn braceless(de: &'a mut Deserializer<'de>) -> Self {
-        MapReader { de, braceless: true }
+        Self::new(de, true)
     }
     pub fn within_braces(de: &'a mut Deserializer<'de>) -> Self {
-        MapReader { de, braceless: false }
+        Self::new(de, false)
+    }
+    fn new(de: &'a mut Deserializer<'de>, braceless: bool) -> Self {
+        let seen_keys = match de.options().duplicate_keys {
+            DuplicateKeys::LastWins => None,
+            _ => Some(HashMap::new()),
+        };
+        MapReader { de, braceless, seen_keys }
+    }
+
+    /// read the next key as a string and tell whether it's the first
+    /// occurrence of this key in the map, or raise an error or a warning,
+    /// depending on the policy.
+    ///
+    /// A first occurrence must then be read again, while the value of
+    /// a skipped one is to be read next.
+    fn check_key(&mut self, seen_keys: &mut HashMap<String, (usize, usize)>) -> Result<bool> {
+        let key_start = self.de.pos();
+        let key = self.de.read_key(PhantomData::<String>)?;
+        let (first_line, first_col) = match seen_keys.get(&key) {
+            Some(&location) => location,
+            None => {
+                let location = self.de.key_location(key_start);
+                seen_keys.insert(key, location);
+                self.de.pop_path();
+                self.de.rewind(key_start);
+                return Ok(true);
+            }
+        };
+        let code = ErrorCode::DuplicateKey {
+            key,
+            line: first_line,
+            col: first_col,
+        };
+        match self.de.options().duplicate_keys {
+            DuplicateKeys::FirstWins => Ok(false),
+            DuplicateKeys::Warn => {
+                self.de.pop_path();
+                self.de.rewind(key_start);
+                let warning = self.de.err(code);
+                self.de.warn(warning);
+                Ok(true)
+            }
+            _ => {
+                self.de.pop_path();
+                self.de.rewind(key_start);
+                self.de.fail(code)
+            }
+        }
+    }
+
+    /// skip the value of a duplicate key, and the optional comma after it
+    fn skip_value(&mut self) -> Result<()> {
+        self.next_value_seed(PhantomData::<IgnoredAny>)?;
+        Ok(())
+    }
+
+    /// eat the spaces and comments before a key (and, unless commas
+    /// are checked, an optional comma)
+    fn eat_shit_before_key(&mut self) -> Result<()> {
+        let options = self.de.options();
+        let eaten = if options.omitted_commas && options.trailing_commas {
+            self.de.eat_shit_and(Some(','))
+        } else {
+            self.de.eat_shit()
+        };
+        self.ignore_braceless_eof(eaten)
+    }
+
+    /// eat the separator which must follow a value
+    fn eat_separator(&mut self) -> Result<()> {
+        let closer = if self.braceless { None } else { Some(b'}') };
+        let eaten = self.de.eat_separator(closer);
+        self.ignore_braceless_eof(eaten)
+    }
+
+    /// read a map value with `f`, then eat the separator which may
+    /// follow it
+    pub(crate) fn next_value_with<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Deserializer<'de>) -> Result<T>,
+    {
+        self.de.eat_shit()?;
+        match f(&mut *self.de) {
+            Err(e) => self.de.cook_err(e),
+            Ok(v) => {
+                self.de.pop_path();
+                self.eat_separator()?;
+                Ok(v)
+            }
+        }
+    }
+
+    /// the end of the input is the end of a braceless map
+    fn ignore_braceless_eof(&self, eaten: Result<()>) -> Result<()> {
+        match eaten {
+            Err(e) if !self.braceless || !self.de.is_clean_eof(&e) => Err(e),
+            _ => Ok(()),
+        }
     }
 }
 
@@ -31,33 +137,33 @@ impl<'de, 'a> MapAccess<'de> for MapReader<'a, 'de> {
     where
         K: DeserializeSeed<'de>,
     {
-        if let Err(e) = self.de.eat_shit_and(Some(',')) {
-            if !self.braceless || !e.is_eof() {
-                return Err(e);
+        loop {
+            self.de.drop_parsed_text();
+            self.eat_shit_before_key()?;
+            if self.braceless && self.de.is_at_root_delimiter() {
+                return Ok(None);
             }
-        }
-        match self.de.peek_byte() {
-            Ok(b'}') => { return Ok(None); }
-            Err(e) => {
-                if e.is_eof() && self.braceless {
-                    return Ok(None);
-                } else {
-                    return Err(e);
+            match self.de.peek_byte() {
+                Ok(b'}') => { return Ok(None); }
+                Err(e) => {
+                    if self.braceless && self.de.is_clean_eof(&e) {
+                        return Ok(None);
+                    } else {
+                        return Err(e);
+                    }
                 }
+                _ => {}
             }
-            _ => {}
-        }
-        // Here's there's a problem: if the key is a string it should be
-        // parsed as an identifier but serde will call deserialize_string.
-        // The problem here is that I thus can't accept colons in quoteless
-        // strings, even when not in a identifier location :\
-        self.de.accept_quoteless_value = false;
-        let v = seed.deserialize(&mut *self.de)?;
-        self.de.eat_shit()?;
-        if self.de.next_byte()? == b':' {
-            Ok(Some(v))
-        } else {
-            self.de.fail(ExpectedMapColon)
+            if let Some(mut seen_keys) = self.seen_keys.take() {
+                let first = self.check_key(&mut seen_keys);
+                self.seen_keys = Some(seen_keys);
+                if !first? {
+                    self.skip_value()?;
+                    continue;
+                }
+            }
+            let v = self.de.read_key(seed)?;
+            return Ok(Some(v));
         }
     }
 
@@ -66,17 +172,6 @@ impl<'de, 'a> MapAccess<'de> for MapReader<'a, 'de> {
     where
         V: DeserializeSeed<'de>,
     {
-        self.de.eat_shit()?;
-        match seed.deserialize(&mut *self.de) {
-            Err(e) => self.de.cook_err(e),
-            Ok(v) => {
-                if let Err(e) = self.de.eat_shit_and(Some(',')) {
-                    if !self.braceless || !e.is_eof() {
-                        return Err(e);
-                    }
-                }
-                Ok(v)
-            }
-        }
+        self.next_value_with(|de| seed.deserialize(de))
     }
 }
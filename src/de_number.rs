@@ -4,48 +4,186 @@ use {
         error::{ErrorCode::*, Result},
     },
     serde::de::Visitor,
+    std::{
+        borrow::Cow,
+        num::ParseIntError,
+    },
 };
 
+#[cfg(feature = "arbitrary_precision")]
+use {
+    crate::error::Error,
+    serde::de::{value::StrDeserializer, DeserializeSeed, IntoDeserializer, MapAccess},
+};
+
+/// The name of the struct, and of its only field, through which the
+/// text of a number is given to the visitor of `deserialize_any` when
+/// the `arbitrary_precision` feature is enabled.
+///
+/// It's the one of serde_json, so that types supporting serde_json's
+/// arbitrary precision (e.g. decimal types) can be read without
+/// going through `f64`.
+pub(crate) const NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+/// The integer types, which can be parsed in any radix
+pub(crate) trait Integer: Sized {
+    fn from_str_radix(s: &str, radix: u32) -> std::result::Result<Self, ParseIntError>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty)*) => {
+        $(
+            impl Integer for $t {
+                fn from_str_radix(s: &str, radix: u32) -> std::result::Result<Self, ParseIntError> {
+                    <$t>::from_str_radix(s, radix)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8 i16 i32 i64 i128 u8 u16 u32 u64 u128);
+
+/// An integer written with a `0x`, `0o` or `0b` prefix, or with
+/// `_` between digits, which the `extended_integers` option allows
+pub(crate) struct ExtendedInteger {
+    /// the number of bytes of the literal
+    pub len: usize,
+    pub radix: u32,
+    /// the sign and digits, without prefix nor underscore
+    pub digits: String,
+}
+
+impl ExtendedInteger {
+    /// read the extended integer at the start of the input, if any
+    /// (an integer which is a plain decimal one isn't returned)
+    pub fn read(input: &[u8]) -> Option<Self> {
+        let negative = input.first() == Some(&b'-');
+        let mut idx = negative as usize;
+        let radix = match input.get(idx..idx + 2) {
+            Some(b"0x") => 16,
+            Some(b"0o") => 8,
+            Some(b"0b") => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            idx += 2;
+        }
+        let is_digit = |idx: usize| {
+            input.get(idx).is_some_and(|&b| (b as char).is_digit(radix))
+        };
+        let mut digits = String::new();
+        if negative {
+            digits.push('-');
+        }
+        let start = idx;
+        loop {
+            if is_digit(idx) {
+                digits.push(input[idx] as char);
+            } else if !(input.get(idx) == Some(&b'_') && idx > start && is_digit(idx + 1)) {
+                break;
+            }
+            idx += 1;
+        }
+        if idx == start || (radix == 10 && !input[start..idx].contains(&b'_')) {
+            return None;
+        }
+        Some(Self { len: idx, radix, digits })
+    }
+}
+
 /// an intermediate representation of number which
 /// are read into undefinite types
 pub(crate) struct Number<'de> {
     negative: bool,
-    s: &'de str,
+    s: Cow<'de, str>,
     has_float_chars: bool,
+    /// 10 unless it's an extended integer
+    radix: u32,
 }
 
 impl<'de> Number<'de> {
-    /// read the characters of the coming floating point number, without parsing.
-    /// The sign at the start is assumed to have been already read
-    pub fn read<'a>(
-        de: &'a mut Deserializer<'de>,
-    ) -> Result<Self> {
-        de.eat_shit()?;
-        let mut negative = false;
-        let mut has_float_chars = false;
-        for (idx, ch) in de.input().char_indices() {
-            match ch {
-                '0'..='9' => { }
-                '-' if idx == 0 => {
-                    negative = true;
+    /// read the coming number, if it's a valid JSON number followed only
+    /// by spaces, a comment or a separator on its line.
+    /// Nothing is consumed when it's not a number but the start of
+    /// a quoteless string (e.g. `3 apples` or `1.2.3`).
+    /// With the `extended_integers` option, integers like `0xFF`
+    /// or `1_000` are numbers too.
+    pub fn try_read(de: &mut Deserializer<'de>) -> Option<Self> {
+        de.read_token();
+        let input = de.input().as_bytes();
+        if de.options().extended_integers {
+            if let Some(integer) = ExtendedInteger::read(input) {
+                let magnitude = integer.digits.trim_start_matches('-');
+                if integer.radix == 10 && magnitude.len() > 1 && magnitude.starts_with('0') {
+                    return None; // a leading zero
                 }
-                '-' | '+' | '.' | 'e' | 'E' => {
-                    has_float_chars = true;
-                }
-                _ => {
-                    let s = de.start(idx);
-                    de.advance(idx); // we keep the last char
-                    return Ok(Self {
-                        negative, s, has_float_chars
-                    });
+                if !de.is_literal_end(integer.len) {
+                    return None;
                 }
+                de.advance(integer.len);
+                return Some(Self {
+                    negative: integer.digits.starts_with('-'),
+                    s: Cow::Owned(integer.digits),
+                    has_float_chars: false,
+                    radix: integer.radix,
+                });
+            }
+        }
+        let digits = |idx: usize| input[idx..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let negative = input.first() == Some(&b'-');
+        let mut idx = negative as usize;
+        let int_len = digits(idx);
+        if int_len == 0 || (int_len > 1 && input[idx] == b'0') {
+            return None; // no digit or a leading zero
+        }
+        idx += int_len;
+        let mut has_float_chars = false;
+        if input.get(idx) == Some(&b'.') {
+            let frac_len = digits(idx + 1);
+            if frac_len == 0 {
+                return None;
             }
+            idx += 1 + frac_len;
+            has_float_chars = true;
         }
-        let s = de.take_all();
-        Ok(Self {
-            negative, s, has_float_chars
+        if matches!(input.get(idx), Some(b'e') | Some(b'E')) {
+            idx += 1;
+            if matches!(input.get(idx), Some(b'+') | Some(b'-')) {
+                idx += 1;
+            }
+            let exp_len = digits(idx);
+            if exp_len == 0 {
+                return None;
+            }
+            idx += exp_len;
+            has_float_chars = true;
+        }
+        if !de.is_literal_end(idx) {
+            return None;
+        }
+        let s = de.start(idx);
+        de.advance(idx);
+        Some(Self {
+            negative, s, has_float_chars, radix: 10,
         })
     }
+    /// the text of the number as written in the source, or, for a
+    /// number which isn't in decimal, its decimal value
+    pub fn json_text(&self, de: &Deserializer<'de>) -> Result<Cow<'_, str>> {
+        if self.radix == 10 {
+            return Ok(Cow::Borrowed(&self.s));
+        }
+        Ok(Cow::Owned(if self.negative {
+            i128::from_str_radix(&self.s, self.radix)
+                .map_err(|_| de.err(ExpectedI128))?
+                .to_string()
+        } else {
+            u128::from_str_radix(&self.s, self.radix)
+                .map_err(|_| de.err(ExpectedU128))?
+                .to_string()
+        }))
+    }
     /// deserialize into a relevant number type
     pub fn visit<'a, V>(
         &self,
@@ -55,21 +193,97 @@ impl<'de> Number<'de> {
     where
         V: Visitor<'de>,
     {
-        if self.has_float_chars {
-            // this is a floating point number (or an error)
-            let v: f64 = self.s.parse()
-                .map_err(|_| de.err(ExpectedF64))?;
-            visitor.visit_f64(v)
-        } else if self.negative {
-            // this is a negative integer (or an error)
-            let v: i64 = self.s.parse()
-                .map_err(|_| de.err(ExpectedI64))?;
-            visitor.visit_i64(v)
-        } else {
-            // this is a positive integer (or a number)
-            let v: u64 = self.s.parse()
-                .map_err(|_| de.err(ExpectedU64))?;
-            visitor.visit_u64(v)
+        if !self.has_float_chars {
+            // integers which don't fit in 64 bits are tried on 128 bits,
+            // then read as floats
+            // (only decimal integers may be read as floats)
+            if self.negative {
+                if let Ok(v) = i64::from_str_radix(&self.s, self.radix) {
+                    return visitor.visit_i64(v);
+                }
+                if let Ok(v) = i128::from_str_radix(&self.s, self.radix) {
+                    return visitor.visit_i128(v);
+                }
+                if self.radix != 10 {
+                    return de.fail(ExpectedI128);
+                }
+            } else {
+                if let Ok(v) = u64::from_str_radix(&self.s, self.radix) {
+                    return visitor.visit_u64(v);
+                }
+                if let Ok(v) = u128::from_str_radix(&self.s, self.radix) {
+                    return visitor.visit_u128(v);
+                }
+                if self.radix != 10 {
+                    return de.fail(ExpectedU128);
+                }
+            }
         }
+        self.visit_float(de, visitor)
+    }
+    /// deserialize a number which isn't an integer of at most 128 bits
+    #[cfg(not(feature = "arbitrary_precision"))]
+    fn visit_float<'a, V>(
+        &self,
+        de: &'a mut Deserializer<'de>,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let v: f64 = self.s.parse()
+            .map_err(|_| de.err(ExpectedF64))?;
+        visitor.visit_f64(v)
+    }
+    /// give the exact text of a number which isn't an integer of
+    /// at most 128 bits
+    #[cfg(feature = "arbitrary_precision")]
+    fn visit_float<'a, V>(
+        &self,
+        _de: &'a mut Deserializer<'de>,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(RawNumber::new(&self.s))
+    }
+}
+
+/// The map with a single `NUMBER_TOKEN` entry whose value is the
+/// text of a number
+#[cfg(feature = "arbitrary_precision")]
+pub(crate) struct RawNumber<'a> {
+    text: Option<&'a str>,
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl<'a> RawNumber<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self { text: Some(text) }
+    }
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl<'de, 'a> MapAccess<'de> for RawNumber<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.text.is_none() {
+            return Ok(None);
+        }
+        let key: StrDeserializer<Error> = NUMBER_TOKEN.into_deserializer();
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let text: StrDeserializer<Error> = self.text.take().unwrap_or_default().into_deserializer();
+        seed.deserialize(text)
     }
 }
@@ -0,0 +1,118 @@
+use {
+    crate::error::Error,
+    std::io::{self, BufRead},
+};
+
+/// The maximal number of bytes read at once from the reader
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// The text of a deserializer reading from a `BufRead`.
+///
+/// The bytes are read by chunks of bounded size, whatever the length
+/// of the lines, and checked to be valid UTF8 one chunk at a time.
+/// The start of the text, once parsed, can be dropped.
+pub(crate) struct ChunkReader<'de> {
+    reader: Box<dyn BufRead + 'de>,
+    /// the bytes read and not yet in the text, which are
+    /// the start of a char whose end isn't read yet
+    pending: Vec<u8>,
+    /// the text read and not dropped
+    pub text: String,
+    /// the position of the start of `text` in the whole input, in bytes
+    pub offset: usize,
+    /// the line and col (in chars) of the start of `text`
+    pub line: usize,
+    pub col: usize,
+    /// whether there's nothing more to read
+    pub eof: bool,
+    /// the error which stopped the reading, if any
+    pub error: Option<Error>,
+}
+
+impl<'de> ChunkReader<'de> {
+    pub fn new<R: BufRead + 'de>(reader: R) -> Self {
+        ChunkReader {
+            reader: Box::new(reader),
+            pending: Vec::new(),
+            text: String::new(),
+            offset: 0,
+            line: 1,
+            col: 1,
+            eof: false,
+            error: None,
+        }
+    }
+
+    /// read the next chunk at the end of the text, return false when
+    /// there's nothing more to read.
+    ///
+    /// A reading error ends the input (the error is kept).
+    pub fn read_chunk(&mut self) -> bool {
+        if self.eof {
+            return false;
+        }
+        let chunk = loop {
+            match self.reader.fill_buf() {
+                Ok(chunk) => break chunk,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.error = Some(e.into());
+                    self.eof = true;
+                    return false;
+                }
+            }
+        };
+        if chunk.is_empty() {
+            if !self.pending.is_empty() {
+                // the input ends in the middle of a char
+                self.error = Some(utf8_error(self.offset + self.text.len(), &self.pending));
+            }
+            self.eof = true;
+            return false;
+        }
+        let len = chunk.len().min(CHUNK_SIZE);
+        self.pending.extend_from_slice(&chunk[..len]);
+        self.reader.consume(len);
+        let valid_len = match std::str::from_utf8(&self.pending) {
+            Ok(s) => s.len(),
+            // the end of the chunk may be the start of a char
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => {
+                self.error = Some(utf8_error(self.offset + self.text.len(), &self.pending));
+                self.eof = true;
+                return false;
+            }
+        };
+        // those bytes have just been checked
+        self.text.push_str(std::str::from_utf8(&self.pending[..valid_len]).unwrap());
+        self.pending.drain(..valid_len);
+        true
+    }
+
+    /// drop the first `len` bytes of the text, which must be
+    /// at a char boundary
+    pub fn drop_start(&mut self, len: usize) {
+        for ch in self.text[..len].chars() {
+            if ch == '\n' {
+                self.line += 1;
+                self.col = 1;
+            } else {
+                self.col += 1;
+            }
+        }
+        self.text.drain(..len);
+        self.offset += len;
+    }
+}
+
+/// build the error of bytes which aren't valid UTF8 and come after
+/// `before` bytes, as it would be when checking the whole input at once
+#[cold]
+fn utf8_error(before: usize, bytes: &[u8]) -> Error {
+    // there's no way to build an Utf8Error with another position, so
+    // we check the bytes again after as many spaces as there were
+    // bytes before them
+    let mut padded = vec![b' '; before];
+    padded.extend_from_slice(bytes);
+    std::str::from_utf8(&padded).unwrap_err().into()
+}
@@ -10,11 +10,37 @@ use {
 /// is used to deserialize arrays
 pub struct SeqReader<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
+    /// index of the next item
+    idx: usize,
 }
 
 impl<'a, 'de> SeqReader<'a, 'de> {
     pub fn new(de: &'a mut Deserializer<'de>) -> Self {
-        SeqReader { de }
+        SeqReader { de, idx: 0 }
+    }
+
+    /// read the next array item with `f`, if any, then eat the
+    /// separator which may follow it
+    pub(crate) fn next_element_with<T, F>(&mut self, f: F) -> Result<Option<T>>
+    where
+        F: FnOnce(&mut Deserializer<'de>) -> Result<T>,
+    {
+        self.de.drop_parsed_text();
+        self.de.eat_shit()?;
+        if self.de.peek_byte()? == b']' {
+            return Ok(None);
+        }
+        self.de.push_index(self.idx);
+        let v = match f(&mut *self.de) {
+            Ok(v) => v,
+            Err(e) => {
+                return self.de.cook_err(e);
+            }
+        };
+        self.de.pop_path();
+        self.idx += 1;
+        self.de.eat_separator(Some(b']'))?;
+        Ok(Some(v))
     }
 }
 
@@ -28,12 +54,6 @@ impl<'de, 'a> SeqAccess<'de> for SeqReader<'a, 'de> {
     where
         T: DeserializeSeed<'de>,
     {
-        self.de.eat_shit()?;
-        if self.de.peek_byte()? == b']' {
-            return Ok(None);
-        }
-        let v = seed.deserialize(&mut *self.de)?;
-        self.de.eat_shit_and(Some(','))?;
-        Ok(Some(v))
+        self.next_element_with(|de| seed.deserialize(de))
     }
 }
@@ -0,0 +1,88 @@
+use {
+    crate::{
+        de::Deserializer,
+        error::{Error, Result},
+        spanned::*,
+    },
+    serde::de::{
+        value::BorrowedStrDeserializer,
+        DeserializeSeed, IntoDeserializer, MapAccess,
+    },
+};
+
+/// an implementation of serde's MapAccess interface which
+/// gives the fields of a `Spanned` to its visitor: first the
+/// location of the start, then the value, and at last the end
+pub struct SpannedReader<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    start: usize,
+    line: usize,
+    col: usize,
+    end: Option<usize>,
+    // index of the next field to give
+    field_idx: usize,
+}
+
+const FIELD_ORDER: &[&str] = &[
+    START_FIELD,
+    LINE_FIELD,
+    COL_FIELD,
+    VALUE_FIELD,
+    END_FIELD,
+];
+
+impl<'a, 'de> SpannedReader<'a, 'de> {
+    pub fn new(
+        de: &'a mut Deserializer<'de>,
+        start: usize,
+        line: usize,
+        col: usize,
+    ) -> Self {
+        SpannedReader {
+            de,
+            start,
+            line,
+            col,
+            end: None,
+            field_idx: 0,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for SpannedReader<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match FIELD_ORDER.get(self.field_idx) {
+            Some(&field) => seed
+                .deserialize(BorrowedStrDeserializer::new(field))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let field = FIELD_ORDER.get(self.field_idx).copied();
+        self.field_idx += 1;
+        match field {
+            Some(START_FIELD) => seed.deserialize(self.start.into_deserializer()),
+            Some(LINE_FIELD) => seed.deserialize(self.line.into_deserializer()),
+            Some(COL_FIELD) => seed.deserialize(self.col.into_deserializer()),
+            Some(VALUE_FIELD) => {
+                let v = seed.deserialize(&mut *self.de)?;
+                self.end = Some(self.de.value_end(self.start));
+                Ok(v)
+            }
+            _ => {
+                let end = self.end.unwrap_or(self.start);
+                seed.deserialize(end.into_deserializer())
+            }
+        }
+    }
+}
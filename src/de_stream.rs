@@ -0,0 +1,116 @@
+use {
+    crate::{
+        de::Deserializer,
+        error::Result,
+    },
+    serde::de::Deserialize,
+    std::marker::PhantomData,
+};
+
+/// An iterator over the Hjson values following each other in
+/// a text, for example `{a: 1} {a: 2}` or maps separated by
+/// newlines.
+///
+/// It's built with [Deserializer::into_iter]. There's no braceless
+/// root map in a stream, and the iteration stops after the first error.
+///
+/// ```
+/// use {
+///     deser_hjson::Deserializer,
+///     serde::Deserialize,
+/// };
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Record {
+///     level: String,
+///     message: String,
+/// }
+///
+/// let hjson = r#"
+/// { level: "info", message: "starting" }
+/// {
+///     level: warn
+///     message: low memory
+/// }
+/// "#;
+/// let mut stream = Deserializer::from_str(hjson).into_iter::<Record>();
+/// assert_eq!(stream.next().unwrap().unwrap().level, "info");
+/// assert_eq!(stream.byte_offset(), 39);
+/// assert_eq!(stream.next().unwrap().unwrap().message, "low memory");
+/// assert!(stream.next().is_none());
+/// ```
+pub struct StreamDeserializer<'de, T> {
+    de: Deserializer<'de>,
+    /// where the last value read started
+    value_start: usize,
+    /// where the last value read ended
+    offset: usize,
+    failed: bool,
+    output: PhantomData<T>,
+}
+
+impl<'de, T> StreamDeserializer<'de, T>
+where
+    T: Deserialize<'de>,
+{
+    pub(crate) fn new(de: Deserializer<'de>) -> Self {
+        let offset = de.pos();
+        StreamDeserializer {
+            de,
+            value_start: offset,
+            offset,
+            failed: false,
+            output: PhantomData,
+        }
+    }
+
+    /// the position in bytes of the end of the last value read
+    /// successfully (or of the start of the stream)
+    ///
+    /// After an error, this tells where the stream can be resumed
+    /// or what part of the text is valid.
+    pub fn byte_offset(&self) -> usize {
+        self.offset
+    }
+
+    /// the position in bytes of the start of the last value read
+    pub fn value_offset(&self) -> usize {
+        self.value_start
+    }
+}
+
+impl<'de, T> Iterator for StreamDeserializer<'de, T>
+where
+    T: Deserialize<'de>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        if self.failed {
+            return None;
+        }
+        self.de.drop_parsed_text();
+        if let Err(e) = self.de.eat_shit() {
+            self.failed = true;
+            // the input may have been ended by a reading error
+            if let Some(read_error) = self.de.take_read_error() {
+                return Some(Err(read_error));
+            }
+            if self.de.input().is_empty() {
+                return None;
+            }
+            return Some(Err(e));
+        }
+        self.value_start = self.de.pos();
+        match T::deserialize(&mut self.de).or_else(|e| self.de.cook_err(e)) {
+            Ok(value) => {
+                self.offset = self.de.value_end(self.value_start);
+                Some(Ok(value))
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(self.de.take_read_error().unwrap_or(e)))
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use {
+    crate::{
+        de::Deserializer,
+        de_map::MapReader,
+        de_number::Number,
+        de_seq::SeqReader,
+        error::{ErrorCode::*, Result},
+        ser::write_json_string,
+    },
+    serde::de::MapAccess,
+    std::{
+        io,
+        marker::PhantomData,
+    },
+};
+
+/// A JSON writer fed while going through the Hjson document, so
+/// that no intermediate value is built.
+///
+/// Numbers are written as they're found in the source, and
+/// map entries in their order.
+pub struct Transcoder<W> {
+    writer: W,
+    pretty: bool,
+    /// current nesting depth (used for indentation in pretty mode)
+    depth: usize,
+}
+
+impl<W: io::Write> Transcoder<W> {
+    pub fn new(writer: W, pretty: bool) -> Self {
+        Transcoder {
+            writer,
+            pretty,
+            depth: 0,
+        }
+    }
+
+    fn write(&mut self, s: &str) -> Result<()> {
+        self.writer.write_all(s.as_bytes())?;
+        Ok(())
+    }
+
+    /// in pretty mode, go to a new line and write the indentation
+    fn new_line(&mut self) -> Result<()> {
+        if self.pretty {
+            self.write("\n")?;
+            for _ in 0..self.depth {
+                self.write("  ")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// read the value at the current position and write it
+    pub fn value(&mut self, de: &mut Deserializer<'_>) -> Result<()> {
+        if de.is_at_braceless_root()? {
+            return de.nested(|de| self.map(MapReader::braceless(de)));
+        }
+        if de.peek_non_finite_float()?.is_some() {
+            return de.fail(NonFiniteFloat);
+        }
+        match de.peek_byte()? {
+            b'{' => de.nested(|de| {
+                de.advance(1);
+                self.map(MapReader::within_braces(de))?;
+                de.eat_shit()?;
+                de.expect_byte(b'}', ExpectedMapEnd)
+            }),
+            b'[' => de.nested(|de| {
+                de.advance(1);
+                self.seq(SeqReader::new(de))?;
+                de.expect_byte(b']', ExpectedArrayEnd)
+            }),
+            b'"' | b'\'' => self.string(de),
+            b'0'..=b'9' | b'-' => match Number::try_read(de) {
+                Some(number) => {
+                    let text = number.json_text(de)?;
+                    self.write(&text)
+                }
+                None => self.string(de),
+            },
+            _ => {
+                for literal in ["null", "true", "false"] {
+                    if de.try_read_literal(literal.as_bytes()) {
+                        return self.write(literal);
+                    }
+                }
+                self.string(de)
+            }
+        }
+    }
+
+    fn string(&mut self, de: &mut Deserializer<'_>) -> Result<()> {
+        let s = de.parse_string_value()?;
+        write_json_string(&mut self.writer, &s)
+    }
+
+    fn map(&mut self, mut map: MapReader<'_, '_>) -> Result<()> {
+        self.write("{")?;
+        self.depth += 1;
+        let mut empty = true;
+        while let Some(key) = map.next_key_seed(PhantomData::<String>)? {
+            if !empty {
+                self.write(",")?;
+            }
+            empty = false;
+            self.new_line()?;
+            write_json_string(&mut self.writer, &key)?;
+            self.write(if self.pretty { ": " } else { ":" })?;
+            map.next_value_with(|de| self.value(de))?;
+        }
+        self.depth -= 1;
+        if !empty {
+            self.new_line()?;
+        }
+        self.write("}")
+    }
+
+    fn seq(&mut self, mut seq: SeqReader<'_, '_>) -> Result<()> {
+        self.write("[")?;
+        self.depth += 1;
+        let mut empty = true;
+        loop {
+            let separator = if empty { "" } else { "," };
+            let item = seq.next_element_with(|de| {
+                self.write(separator)?;
+                self.new_line()?;
+                self.value(de)
+            })?;
+            if item.is_none() {
+                break;
+            }
+            empty = false;
+        }
+        self.depth -= 1;
+        if !empty {
+            self.new_line()?;
+        }
+        self.write("]")
+    }
+}
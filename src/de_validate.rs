@@ -0,0 +1,235 @@
+use {
+    crate::{
+        de::Deserializer,
+        error::{Error, ErrorCode::{self, *}},
+    },
+    serde::de::{self, IgnoredAny},
+};
+
+/// A checker going through the whole document and collecting
+/// syntax errors instead of stopping at the first one.
+///
+/// After an error, it skips to the end of the line or to the
+/// next closing bracket, then goes on.
+pub struct Validator<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    errors: Vec<Error>,
+    /// where the last error was found, so that we don't
+    /// loop on the same problem
+    last_error_pos: Option<usize>,
+    /// the closing brackets of the maps and arrays we're in
+    closers: Vec<u8>,
+}
+
+impl<'a, 'de> Validator<'a, 'de> {
+    pub fn new(de: &'a mut Deserializer<'de>) -> Self {
+        Validator {
+            de,
+            errors: Vec::new(),
+            last_error_pos: None,
+            closers: Vec::new(),
+        }
+    }
+
+    /// check the whole document and return the errors found
+    pub fn validate(mut self) -> Vec<Error> {
+        let braceless = self.de.pos() == 0 && self.de.options().braceless_root;
+        match self.de.eat_shit() {
+            Err(e) => {
+                // an empty document is a valid braceless map
+                if !braceless || !self.de.is_clean_eof(&e) {
+                    self.recover(e);
+                }
+            }
+            Ok(()) if braceless && self.de.is_at_map_key() => {
+                match self.de.enter_nested() {
+                    Ok(()) => {
+                        self.map(false);
+                        self.de.leave_nested();
+                    }
+                    Err(e) => self.recover(e),
+                }
+            }
+            Ok(()) => self.value(),
+        }
+        if let Err(e) = self.de.check_all_consumed() {
+            self.recover(e);
+        }
+        self.errors
+    }
+
+    /// record the error, unless we're still at the place of the
+    /// previous one, and tell whether it was recorded
+    fn record(&mut self, err: Error) -> bool {
+        let pos = self.de.pos();
+        if self.last_error_pos == Some(pos) {
+            false
+        } else {
+            self.errors.push(err);
+            self.last_error_pos = Some(pos);
+            true
+        }
+    }
+
+    /// record the error then skip to the next newline or closing bracket
+    fn recover(&mut self, err: Error) {
+        if !self.record(err) {
+            // we didn't move since the last error, we must skip
+            // the char which causes it
+            if let Ok(ch) = self.de.peek_char() {
+                self.de.drop(ch);
+            }
+        }
+        while let Ok(ch) = self.de.peek_char() {
+            match ch {
+                '}' | ']' => break,
+                '\n' => {
+                    self.de.drop(ch);
+                    break;
+                }
+                _ => self.de.drop(ch),
+            }
+        }
+        self.de.accept_quoteless_value = true;
+    }
+
+    /// if the next char is a closing bracket, tell whether it ends the
+    /// current map or array, recording an error when it's the closing
+    /// bracket of an enclosing one (which we leave to it)
+    fn is_at_end(&mut self, code: ErrorCode) -> bool {
+        let closer = match self.closers.last() {
+            Some(&closer) => closer,
+            None => return false,
+        };
+        match self.de.peek_byte() {
+            Ok(b) if b == closer => {
+                self.de.advance(1);
+                true
+            }
+            Ok(b) if self.closers.contains(&b) => {
+                let err = self.de.err(code);
+                self.record(err);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// check a value, after the eventual spaces and comments
+    fn value(&mut self) {
+        if let Err(e) = self.de.eat_shit() {
+            self.recover(e);
+            return;
+        }
+        match self.de.peek_byte() {
+            Ok(b @ b'{') | Ok(b @ b'[') => {
+                if let Err(e) = self.de.enter_nested() {
+                    self.recover(e);
+                    return;
+                }
+                self.de.advance(1);
+                if b == b'{' {
+                    self.map(true);
+                } else {
+                    self.seq();
+                }
+                self.de.leave_nested();
+            }
+            _ => {
+                if let Err(e) = de::Deserializer::deserialize_any(&mut *self.de, IgnoredAny) {
+                    self.recover(e);
+                }
+            }
+        }
+    }
+
+    /// check the entries of a map, whose opening brace, if
+    /// any, has already been consumed
+    fn map(&mut self, within_braces: bool) {
+        if within_braces {
+            self.closers.push(b'}');
+            self.map_entries(true);
+            self.closers.pop();
+        } else {
+            self.map_entries(false);
+        }
+    }
+
+    fn map_entries(&mut self, within_braces: bool) {
+        loop {
+            if let Err(e) = self.de.eat_shit_and(Some(',')) {
+                // the end of the input ends a braceless map, but
+                // not an unclosed comment
+                if within_braces || !self.de.is_clean_eof(&e) {
+                    self.recover(e);
+                }
+                return;
+            }
+            if within_braces && self.is_at_end(ExpectedMapEnd) {
+                return;
+            }
+            self.de.accept_quoteless_value = false;
+            let key_start = self.de.pos();
+            let key = de::Deserializer::deserialize_str(&mut *self.de, IgnoredAny);
+            self.de.accept_quoteless_value = true;
+            if let Err(e) = key {
+                self.recover(e);
+                continue;
+            }
+            self.de.push_key(key_start);
+            let colon = self.de.eat_shit()
+                .and_then(|_| self.de.expect_byte(b':', ExpectedMapColon));
+            match colon {
+                Ok(()) => {
+                    self.value();
+                    let closer = if within_braces { Some(b'}') } else { None };
+                    self.separator(closer);
+                }
+                Err(e) => self.recover(e),
+            }
+            self.de.pop_path();
+        }
+    }
+
+    /// check the items of an array, whose opening bracket
+    /// has already been consumed
+    fn seq(&mut self) {
+        self.closers.push(b']');
+        self.seq_items();
+        self.closers.pop();
+    }
+
+    fn seq_items(&mut self) {
+        let mut idx = 0;
+        loop {
+            if let Err(e) = self.de.eat_shit() {
+                self.recover(e);
+                return;
+            }
+            if self.is_at_end(ExpectedArrayEnd) {
+                return;
+            }
+            self.de.push_index(idx);
+            self.value();
+            self.de.pop_path();
+            idx += 1;
+            if let Err(e) = self.de.eat_separator(Some(b']')) {
+                let eof = e.is_eof();
+                self.recover(e);
+                if eof {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// check the separator after a map value, the end of the input
+    /// being left to the caller
+    fn separator(&mut self, closer: Option<u8>) {
+        if let Err(e) = self.de.eat_separator(closer) {
+            if !self.de.is_clean_eof(&e) {
+                self.recover(e);
+            }
+        }
+    }
+}
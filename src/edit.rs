@@ -0,0 +1,667 @@
+//! An editable Hjson document, preserving comments and formatting.
+//!
+//! A [Document] keeps everything which was in the source: comments,
+//! spaces, commas, quotes, the absence of braces around the root map.
+//! Values are read and modified with paths, and the parts of the
+//! document which weren't modified are written back unchanged.
+//!
+//! ```
+//! use deser_hjson::{*, edit::Document};
+//!
+//! let hjson = "\
+//! // the server config
+//! name: main server
+//! port: 8080 # the default one
+//! tags: [
+//!     web
+//!     'prod'
+//! ]
+//! ";
+//! let mut doc: Document = hjson.parse().unwrap();
+//! assert_eq!(doc.get(&"port".parse().unwrap()), Some(Value::from(8080u64)));
+//! doc.set(&"port".parse().unwrap(), Value::from(9090u64)).unwrap();
+//! doc.insert(&"tags[2]".parse().unwrap(), Value::from("new")).unwrap();
+//! doc.insert(&"debug".parse().unwrap(), Value::from(false)).unwrap();
+//! doc.remove(&"tags[0]".parse().unwrap());
+//! assert_eq!(doc.to_string(), "\
+//! // the server config
+//! name: main server
+//! port: 9090 # the default one
+//! tags: [
+//!     'prod'
+//!     new
+//! ]
+//! debug: false
+//! ");
+//! ```
+
+use {
+    crate::{
+        de::Deserializer,
+        error::{ErrorCode::*, Result},
+        options::DeserializerOptions,
+        path::*,
+        ser,
+        value::{Map, Value},
+    },
+    serde::Deserialize,
+    std::{
+        fmt,
+        marker::PhantomData,
+        str::FromStr,
+    },
+};
+
+/// A Hjson document which can be modified without losing
+/// its comments and formatting
+#[derive(Debug, Clone)]
+pub struct Document {
+    /// spaces and comments before the root value (empty when
+    /// the root is a braceless map, which holds them)
+    before: String,
+    root: Node,
+    /// spaces and comments after the root value
+    after: String,
+}
+
+/// The error returned when a document can't be modified
+/// at the given path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+    /// there's no value at this path
+    NotFound(Path),
+    /// the value at this path is neither a map nor an array
+    /// (or not the one the next key or index needs)
+    NotAContainer(Path),
+    /// the value can't be written in the document (the
+    /// message tells why)
+    InvalidValue(String),
+}
+
+/// A value, with the formatting of its content
+#[derive(Debug, Clone)]
+enum Node {
+    Scalar {
+        /// the value as it's written in the source
+        raw: String,
+        value: Value,
+    },
+    Map {
+        braceless: bool,
+        entries: Container<Entry>,
+    },
+    Array(Container<Node>),
+}
+
+/// A map entry, with its key as written in the source
+#[derive(Debug, Clone)]
+struct Entry {
+    key: String,
+    raw_key: String,
+    /// what's between the key and the value, including the colon
+    between: String,
+    value: Node,
+}
+
+/// A map entry or array item, with the spaces and comments around it
+#[derive(Debug, Clone)]
+struct Item<T> {
+    /// spaces, comments and newlines before the item
+    before: String,
+    inner: T,
+    /// what follows the item on the same line (spaces, a comma,
+    /// a comment)
+    after: String,
+}
+
+/// The content of a map or an array
+#[derive(Debug, Clone)]
+struct Container<T> {
+    items: Vec<Item<T>>,
+    /// spaces and comments after the last item
+    trailing: String,
+}
+
+/// a parser of Hjson keeping all the text of the source
+struct Parser<'de> {
+    src: &'de str,
+    de: Deserializer<'de>,
+}
+
+impl<'de> Parser<'de> {
+    fn new(src: &'de str) -> Self {
+        // we detect the braceless root ourselves
+        let options = DeserializerOptions::new().braceless_root(false);
+        Self {
+            src,
+            de: Deserializer::with_options(src, options),
+        }
+    }
+
+    /// return the position of the end of the node which was just read
+    /// (a scalar may have been read with the newline following it)
+    fn end_of(&self, node: &Node, start: usize) -> usize {
+        match node {
+            Node::Scalar { .. } => self.de.value_end(start),
+            _ => self.de.pos(),
+        }
+    }
+
+    /// read the spaces and comments (and maybe a comma) starting at `start`
+    fn trivia(&mut self, start: usize, comma: bool) -> Result<&'de str> {
+        let read = if comma {
+            self.de.eat_shit_and(Some(','))
+        } else {
+            self.de.eat_shit()
+        };
+        match read {
+            // the end of the input is checked by the caller, but an
+            // unclosed comment leaves some input
+            Err(e) if !self.de.is_clean_eof(&e) => Err(e),
+            _ => Ok(&self.src[start..self.de.pos()]),
+        }
+    }
+
+    fn document(mut self) -> Result<Document> {
+        let before = self.trivia(0, false)?;
+        let braceless = self.de.input().is_empty() || self.de.is_at_map_key();
+        let (before, root) = if braceless {
+            self.de.enter_nested()?;
+            let map = self.map(true, before);
+            self.de.leave_nested();
+            ("", map?)
+        } else {
+            (before, self.value()?)
+        };
+        // a root scalar may have been read with the newline following it
+        let root_start = before.len();
+        let after = self.trivia(self.end_of(&root, root_start), false)?;
+        if !self.de.input().is_empty() {
+            return self.de.fail(TrailingCharacters);
+        }
+        Ok(Document {
+            before: before.to_string(),
+            root,
+            after: after.to_string(),
+        })
+    }
+
+    fn value(&mut self) -> Result<Node> {
+        match self.de.peek_byte()? {
+            b @ b'{' | b @ b'[' => {
+                self.de.enter_nested()?;
+                self.de.advance(1);
+                let node = if b == b'{' {
+                    let before = self.trivia(self.de.pos(), true)?;
+                    self.map(false, before)
+                } else {
+                    self.array()
+                };
+                self.de.leave_nested();
+                node
+            }
+            _ => {
+                let start = self.de.pos();
+                let value = Value::deserialize(&mut self.de)
+                    .or_else(|e| self.de.cook_err(e))?;
+                let end = self.de.value_end(start);
+                Ok(Node::Scalar {
+                    raw: self.src[start..end].to_string(),
+                    value,
+                })
+            }
+        }
+    }
+
+    /// read the entries of a map, after its opening brace (if any)
+    /// and the spaces and comments following it
+    fn map(&mut self, braceless: bool, mut before: &'de str) -> Result<Node> {
+        let mut items = Vec::new();
+        loop {
+            match self.de.peek_byte() {
+                Ok(b'}') if !braceless => {
+                    self.de.advance(1);
+                    break;
+                }
+                Err(e) if !braceless || !self.de.is_clean_eof(&e) => {
+                    return Err(e);
+                }
+                Err(_) => {
+                    break;
+                }
+                Ok(_) => {}
+            }
+            let key_start = self.de.pos();
+            let key = self.de.read_key(PhantomData::<String>)
+                .or_else(|e| self.de.cook_err(e))?;
+            let key_end = self.de.value_end(key_start);
+            self.de.eat_shit()?;
+            let between = &self.src[key_end..self.de.pos()];
+            let value_start = self.de.pos();
+            let value = self.value()?;
+            self.de.pop_path();
+            let (after, next_before) = split_trivia(self.trivia(self.end_of(&value, value_start), true)?);
+            items.push(Item {
+                before: before.to_string(),
+                inner: Entry {
+                    key,
+                    raw_key: self.src[key_start..key_end].to_string(),
+                    between: between.to_string(),
+                    value,
+                },
+                after: after.to_string(),
+            });
+            before = next_before;
+        }
+        Ok(Node::Map {
+            braceless,
+            entries: Container {
+                items,
+                trailing: before.to_string(),
+            },
+        })
+    }
+
+    /// read the items of an array, after its opening bracket
+    fn array(&mut self) -> Result<Node> {
+        let mut items = Vec::new();
+        let mut before = self.trivia(self.de.pos(), false)?;
+        loop {
+            if self.de.peek_byte()? == b']' {
+                self.de.advance(1);
+                break;
+            }
+            let start = self.de.pos();
+            self.de.push_index(items.len());
+            let value = self.value()?;
+            self.de.pop_path();
+            let (after, next_before) = split_trivia(self.trivia(self.end_of(&value, start), true)?);
+            items.push(Item {
+                before: before.to_string(),
+                inner: value,
+                after: after.to_string(),
+            });
+            before = next_before;
+        }
+        Ok(Node::Array(Container {
+            items,
+            trailing: before.to_string(),
+        }))
+    }
+}
+
+/// split the spaces and comments following an item into the part
+/// on the item's line (up to the comma, if the line ends later)
+/// and the part before the next item
+fn split_trivia(trivia: &str) -> (&str, &str) {
+    let idx = match trivia.find('\n') {
+        Some(idx) if !trivia[..idx].contains("/*") => idx,
+        Some(_) => 0,
+        None => trivia.find(',').map_or(0, |idx| idx + 1),
+    };
+    trivia.split_at(idx)
+}
+
+/// the indentation of the line on which an item starts
+fn indent_of(before: &str) -> &str {
+    let line = before.rsplit('\n').next().unwrap_or("");
+    if before.contains('\n') && line.chars().all(char::is_whitespace) {
+        line
+    } else {
+        ""
+    }
+}
+
+/// write a value in the style of the place where it goes, and read it
+/// back as a node
+fn new_node(value: &Value, pretty: bool, indent: &str) -> std::result::Result<Node, EditError> {
+    let text = if pretty {
+        crate::to_string_pretty(value)
+    } else {
+        crate::to_string(value)
+    };
+    let text = text
+        .map_err(|e| EditError::InvalidValue(e.to_string()))?
+        .replace('\n', &format!("\n{}", indent));
+    let node = Parser::new(&text)
+        .value()
+        .map_err(|e| EditError::InvalidValue(format!("{} in {:?}", e, text)));
+    node
+}
+
+fn raw_key(key: &str) -> String {
+    if ser::is_quoteless_key_compatible(key) {
+        key.to_string()
+    } else {
+        crate::to_string(key).expect("strings are always serializable")
+    }
+}
+
+impl Node {
+    fn to_value(&self) -> Value {
+        match self {
+            Self::Scalar { value, .. } => value.clone(),
+            Self::Map { entries, .. } => {
+                let mut map = Map::new();
+                for item in &entries.items {
+                    map.insert(item.inner.key.clone(), item.inner.value.to_value());
+                }
+                Value::Map(map)
+            }
+            Self::Array(items) => Value::Array(
+                items.items.iter().map(|item| item.inner.to_value()).collect()
+            ),
+        }
+    }
+    fn is_container(&self) -> bool {
+        !matches!(self, Self::Scalar { .. })
+    }
+    /// return the index of the entry with the given key (the last
+    /// one if the key is duplicated, as it's the one deserialized)
+    fn key_idx(&self, key: &str) -> Option<usize> {
+        match self {
+            Self::Map { entries, .. } => entries.items.iter().rposition(|item| item.inner.key == key),
+            _ => None,
+        }
+    }
+    fn child(&self, segment: &PathSegment) -> Option<&Node> {
+        match (self, segment) {
+            (Self::Map { entries, .. }, PathSegment::Key(key)) => self
+                .key_idx(key)
+                .map(|idx| &entries.items[idx].inner.value),
+            (Self::Array(items), PathSegment::Index(idx)) => items.items.get(*idx).map(|item| &item.inner),
+            _ => None,
+        }
+    }
+    fn child_mut(&mut self, segment: &PathSegment) -> Option<&mut Node> {
+        let idx = match segment {
+            PathSegment::Key(key) => self.key_idx(key),
+            PathSegment::Index(idx) => Some(*idx),
+        };
+        match (self, segment, idx) {
+            (Self::Map { entries, .. }, PathSegment::Key(_), Some(idx)) => Some(&mut entries.items[idx].inner.value),
+            (Self::Array(items), PathSegment::Index(_), Some(idx)) => items.items.get_mut(idx).map(|item| &mut item.inner),
+            _ => None,
+        }
+    }
+    fn write(&self, out: &mut String) {
+        match self {
+            Self::Scalar { raw, .. } => out.push_str(raw),
+            Self::Map { braceless, entries } => {
+                if !braceless {
+                    out.push('{');
+                }
+                for item in &entries.items {
+                    out.push_str(&item.before);
+                    out.push_str(&item.inner.raw_key);
+                    out.push_str(&item.inner.between);
+                    item.inner.value.write(out);
+                    out.push_str(&item.after);
+                }
+                out.push_str(&entries.trailing);
+                if !braceless {
+                    out.push('}');
+                }
+            }
+            Self::Array(items) => {
+                out.push('[');
+                for item in &items.items {
+                    out.push_str(&item.before);
+                    item.inner.write(out);
+                    out.push_str(&item.after);
+                }
+                out.push_str(&items.trailing);
+                out.push(']');
+            }
+        }
+    }
+}
+
+impl<T> Container<T> {
+    /// tell whether the items are on their own lines
+    fn is_multiline(&self, braceless: bool) -> bool {
+        braceless
+            || self.trailing.contains('\n')
+            || self.items.iter().any(|item| item.before.contains('\n'))
+    }
+    /// tell whether the item is the last thing on its line, so that
+    /// a quoteless string can be written there
+    fn ends_line(&self, idx: usize, braceless: bool) -> bool {
+        let mut rest = self.items[idx].after.clone();
+        rest.push_str(match self.items.get(idx + 1) {
+            Some(next) => &next.before,
+            None => &self.trailing,
+        });
+        match rest.find('\n') {
+            Some(end) => rest[..end].trim().is_empty(),
+            None => braceless && rest.trim().is_empty(),
+        }
+    }
+    /// insert an item at `idx`, choosing spaces and commas around
+    /// it according to the style of the container
+    fn insert(&mut self, idx: usize, inner: T, braceless: bool) {
+        let multiline = self.is_multiline(braceless);
+        let mut item = Item {
+            before: String::new(),
+            inner,
+            after: String::new(),
+        };
+        if self.items.is_empty() {
+            if braceless {
+                // the comments of an empty document stay before
+                // the new item
+                item.before = std::mem::take(&mut self.trailing);
+                if !item.before.is_empty() && !item.before.ends_with('\n') {
+                    item.before.push('\n');
+                }
+                self.trailing = "\n".to_string();
+            } else if multiline {
+                item.before = format!("\n{}  ", indent_of(&self.trailing));
+            }
+        } else if multiline {
+            let model = &self.items[idx.min(self.items.len() - 1)];
+            item.before = format!("\n{}", indent_of(&model.before));
+        } else if idx < self.items.len() {
+            item.before = std::mem::replace(&mut self.items[idx].before, " ".to_string());
+            item.after = ",".to_string();
+        } else {
+            let last = self.items.last_mut().unwrap();
+            if !last.after.contains(',') {
+                last.after.insert(0, ',');
+            }
+            item.before = " ".to_string();
+        }
+        self.items.insert(idx, item);
+    }
+    /// remove the item at `idx`, with the spaces and comments around it
+    fn remove(&mut self, idx: usize) -> T {
+        let item = self.items.remove(idx);
+        if let Some(next) = self.items.get_mut(idx) {
+            if !next.before.contains('\n') {
+                next.before = item.before;
+            }
+        } else if idx > 0 && !item.after.contains(',') {
+            let last = &mut self.items[idx - 1];
+            if let Some(comma) = last.after.find(',') {
+                if !last.after[..comma].contains(['#', '/']) {
+                    last.after.remove(comma);
+                }
+            }
+        }
+        item.inner
+    }
+}
+
+impl Document {
+    /// parse a Hjson document
+    pub fn parse(src: &str) -> Result<Self> {
+        Parser::new(src).document()
+    }
+
+    fn node(&self, path: &Path) -> Option<&Node> {
+        path.iter().try_fold(&self.root, |node, segment| node.child(segment))
+    }
+
+    fn node_mut(&mut self, path: &Path) -> Option<&mut Node> {
+        path.iter().try_fold(&mut self.root, |node, segment| node.child_mut(segment))
+    }
+
+    /// return the node holding the value at `path`, which must be a container
+    fn parent_mut(&mut self, path: &Path) -> std::result::Result<&mut Node, EditError> {
+        let parent_path = path.parent().unwrap_or_default();
+        match self.node_mut(&parent_path) {
+            Some(node) if node.is_container() => Ok(node),
+            Some(_) => Err(EditError::NotAContainer(parent_path)),
+            None => Err(EditError::NotFound(parent_path)),
+        }
+    }
+
+    /// return the value at the given path, if any
+    pub fn get(&self, path: &Path) -> Option<Value> {
+        self.node(path).map(Node::to_value)
+    }
+
+    /// replace the value at the given path, which must exist, keeping
+    /// the comments around it, and return the previous value
+    pub fn set(&mut self, path: &Path, value: Value) -> std::result::Result<Value, EditError> {
+        let last = match path.last() {
+            Some(last) => last,
+            None => {
+                let old = self.root.to_value();
+                self.root = new_node(&value, true, "")?;
+                return Ok(old);
+            }
+        };
+        let parent = self.parent_mut(path)?;
+        let idx = match (&*parent, last) {
+            (Node::Map { .. }, PathSegment::Key(key)) => parent.key_idx(key),
+            (Node::Array(items), PathSegment::Index(idx)) if *idx < items.items.len() => Some(*idx),
+            _ => None,
+        };
+        let idx = idx.ok_or_else(|| EditError::NotFound(path.clone()))?;
+        let node = match parent {
+            Node::Map { braceless, entries } => {
+                let pretty = is_pretty(entries, idx, *braceless, &value);
+                let node = new_node(&value, pretty, indent_of(&entries.items[idx].before))?;
+                std::mem::replace(&mut entries.items[idx].inner.value, node)
+            }
+            Node::Array(items) => {
+                let pretty = is_pretty(items, idx, false, &value);
+                let node = new_node(&value, pretty, indent_of(&items.items[idx].before))?;
+                std::mem::replace(&mut items.items[idx].inner, node)
+            }
+            Node::Scalar { .. } => unreachable!(),
+        };
+        Ok(node.to_value())
+    }
+
+    /// insert a value at the given path: at the end of the parent map
+    /// when the path ends with a key, or before the item at this index
+    /// when it ends with an array index (which may be the array length)
+    ///
+    /// If the key is already in the map, its value is replaced and the
+    /// old value returned.
+    pub fn insert(&mut self, path: &Path, value: Value) -> std::result::Result<Option<Value>, EditError> {
+        if path.is_empty() || self.node(path).is_some() && matches!(path.last(), Some(PathSegment::Key(_))) {
+            return self.set(path, value).map(Some);
+        }
+        let parent_path = path.parent().unwrap_or_default();
+        let parent = self.parent_mut(path)?;
+        match (parent, path.last()) {
+            (Node::Map { braceless, entries }, Some(PathSegment::Key(key))) => {
+                let idx = entries.items.len();
+                let placeholder = Entry {
+                    key: key.clone(),
+                    raw_key: raw_key(key),
+                    between: ": ".to_string(),
+                    value: Node::Scalar { raw: String::new(), value: Value::Null },
+                };
+                entries.insert(idx, placeholder, *braceless);
+                let pretty = is_pretty(entries, idx, *braceless, &value);
+                match new_node(&value, pretty, indent_of(&entries.items[idx].before)) {
+                    Ok(node) => entries.items[idx].inner.value = node,
+                    Err(e) => {
+                        entries.remove(idx);
+                        return Err(e);
+                    }
+                }
+            }
+            (Node::Array(items), Some(&PathSegment::Index(idx))) if idx <= items.items.len() => {
+                let placeholder = Node::Scalar { raw: String::new(), value: Value::Null };
+                items.insert(idx, placeholder, false);
+                let pretty = is_pretty(items, idx, false, &value);
+                match new_node(&value, pretty, indent_of(&items.items[idx].before)) {
+                    Ok(node) => items.items[idx].inner = node,
+                    Err(e) => {
+                        items.remove(idx);
+                        return Err(e);
+                    }
+                }
+            }
+            (Node::Array(_), Some(PathSegment::Index(_))) => {
+                return Err(EditError::NotFound(path.clone()));
+            }
+            _ => {
+                return Err(EditError::NotAContainer(parent_path));
+            }
+        }
+        Ok(None)
+    }
+
+    /// remove the value at the given path, with the comments
+    /// on its lines, and return it
+    pub fn remove(&mut self, path: &Path) -> Option<Value> {
+        let parent = self.parent_mut(path).ok()?;
+        let node = match (parent, path.last()?) {
+            (Node::Map { entries, .. }, PathSegment::Key(key)) => {
+                let idx = entries.items.iter().rposition(|item| &item.inner.key == key)?;
+                entries.remove(idx).value
+            }
+            (Node::Array(items), &PathSegment::Index(idx)) if idx < items.items.len() => {
+                items.remove(idx)
+            }
+            _ => {
+                return None;
+            }
+        };
+        Some(node.to_value())
+    }
+}
+
+/// tell whether the value to write at `idx` should be written in
+/// the human friendly way rather than in the compact one
+fn is_pretty<T>(container: &Container<T>, idx: usize, braceless: bool, value: &Value) -> bool {
+    match value {
+        Value::Map(_) | Value::Array(_) => container.is_multiline(braceless),
+        Value::String(s) => !s.contains('\n') && container.ends_line(idx, braceless),
+        _ => false,
+    }
+}
+
+impl FromStr for Document {
+    type Err = crate::Error;
+    fn from_str(src: &str) -> Result<Self> {
+        Self::parse(src)
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut out = self.before.clone();
+        self.root.write(&mut out);
+        out.push_str(&self.after);
+        f.write_str(&out)
+    }
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound(path) => write!(f, "no value at path {:?}", path.to_string()),
+            Self::NotAContainer(path) => write!(f, "no map or array at path {:?}", path.to_string()),
+            Self::InvalidValue(message) => write!(f, "invalid value: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
@@ -1,5 +1,9 @@
 use {
-    serde::de,
+    crate::{
+        options::HjsonFeature,
+        path::Path,
+    },
+    serde::{de, ser},
     std::{
         fmt,
         io,
@@ -12,6 +16,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// The types of errors which can happen in our code
 /// during deserialization
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum ErrorCode {
     Eof,
     ExpectedBoolean,
@@ -20,10 +25,12 @@ pub enum ErrorCode {
     ExpectedI16,
     ExpectedI32,
     ExpectedI64,
+    ExpectedI128,
     ExpectedU8,
     ExpectedU16,
     ExpectedU32,
     ExpectedU64,
+    ExpectedU128,
     ExpectedF32,
     ExpectedF64,
     ExpectedPositiveInteger,
@@ -38,7 +45,20 @@ pub enum ErrorCode {
     ExpectedMapEnd,
     ExpectedEnum,
     ExpectedSingleChar,
+    /// an Hjson extension disallowed by the parsing options
+    Disallowed(HjsonFeature),
+    /// a key already found in the same map, whose first
+    /// occurrence is at the given line and column
+    DuplicateKey {
+        key: String,
+        line: usize,
+        col: usize,
+    },
     InvalidEscapeSequence,
+    LoneSurrogate,
+    /// a NaN or infinite float, which can't be written in JSON
+    NonFiniteFloat,
+    RecursionLimitExceeded,
     TrailingCharacters,
     UnexpectedChar,
 }
@@ -49,37 +69,191 @@ pub enum Error {
 
     /// a Hjson syntax error raised in our code,
     /// with location
+    #[non_exhaustive]
     Syntax {
         line: usize,
         col: usize, // in chars (tab is one char)
         code: ErrorCode,
         at: String, // next few chars
+        path: Path, // keys and indexes leading to the faulty value
     },
 
     /// A Serde error, with approximate location
+    #[non_exhaustive]
     Serde {
         line: usize,
         col: usize, // in chars (tab is one char)
         message: String,
+        path: Path, // keys and indexes leading to the faulty value
     },
 
     /// a raw Serde error. We should try to
     /// convert them to Serde located errors as
-    /// much as possible
+    /// much as possible.
+    /// Serialization errors are also raw Serde errors.
     RawSerde(String),
 
     /// an UTF8 error, raised when using from_slice
     /// with an invalid UTF8 slice
     Utf8(Utf8Error),
 
-    /// an IO error, raised when using from_reader
+    /// an IO error, raised when using from_reader or to_writer
     Io(io::Error),
 }
 
+impl fmt::Display for ErrorCode {
+    /// write a sentence explaining the error to a human
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Eof => "unexpected end of input",
+            Self::ExpectedBoolean => "expected a boolean",
+            Self::ExpectedInteger => "expected an integer",
+            Self::ExpectedI8 => "expected an integer between -128 and 127",
+            Self::ExpectedI16 => "expected an integer between -32768 and 32767",
+            Self::ExpectedI32 => "expected a 32 bits integer",
+            Self::ExpectedI64 => "expected a 64 bits integer",
+            Self::ExpectedI128 => "expected a 128 bits integer",
+            Self::ExpectedU8 => "expected an integer between 0 and 255",
+            Self::ExpectedU16 => "expected an integer between 0 and 65535",
+            Self::ExpectedU32 => "expected a positive 32 bits integer",
+            Self::ExpectedU64 => "expected a positive 64 bits integer",
+            Self::ExpectedU128 => "expected a positive 128 bits integer",
+            Self::ExpectedF32 => "expected a number",
+            Self::ExpectedF64 => "expected a number",
+            Self::ExpectedPositiveInteger => "expected a positive integer",
+            Self::ExpectedString => "expected a quoted string",
+            Self::ExpectedNull => "expected null",
+            Self::ExpectedArray => "expected an array",
+            Self::ExpectedArrayComma => "expected a comma or the end of the array",
+            Self::ExpectedArrayEnd => "expected the end of the array (`]`)",
+            Self::ExpectedMap => "expected a map",
+            Self::ExpectedMapColon => "expected a colon after the map key",
+            Self::ExpectedMapComma => "expected a comma or the end of the map",
+            Self::ExpectedMapEnd => "expected the end of the map (`}`)",
+            Self::ExpectedEnum => "expected an enum variant",
+            Self::ExpectedSingleChar => "expected a single character",
+            Self::Disallowed(feature) => {
+                return write!(f, "{} are disallowed by the parsing options", feature);
+            }
+            Self::DuplicateKey { key, line, col } => {
+                return write!(f, "duplicate key {:?}, first found at {}:{}", key, line, col);
+            }
+            Self::InvalidEscapeSequence => "invalid escape sequence",
+            Self::LoneSurrogate => "lone UTF-16 surrogate in a `\\u` escape sequence (a high surrogate must be followed by a low one)",
+            Self::NonFiniteFloat => "NaN and infinite floats can't be written in JSON",
+            Self::RecursionLimitExceeded => "too deeply nested maps and arrays",
+            Self::TrailingCharacters => "unexpected characters after the end of the value",
+            Self::UnexpectedChar => "unexpected character",
+        };
+        f.write_str(s)
+    }
+}
+
 impl Error {
     pub fn is_eof(&self) -> bool {
         matches!(self, Error::Syntax { code: ErrorCode::Eof, .. })
     }
+
+    /// return the path, in the document, of the value whose
+    /// deserialization failed, when known
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::Syntax { path, .. } => Some(path),
+            Self::Serde { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// return the line and column of the error, when known
+    pub fn location(&self) -> Option<(usize, usize)> {
+        match self {
+            Self::Syntax { line, col, .. } => Some((*line, *col)),
+            Self::Serde { line, col, .. } => Some((*line, *col)),
+            _ => None,
+        }
+    }
+
+    /// Build a description of the error, intended to be shown to a user,
+    /// with the relevant lines of the source and a caret pointing to the
+    /// error location.
+    ///
+    /// `src` must be the Hjson text in which the error was found.
+    ///
+    /// ```
+    /// let src = "{\n  a: 1\n  b 2\n}";
+    /// let err = deser_hjson::from_str::<deser_hjson::Value>(src).unwrap_err();
+    /// assert_eq!(
+    ///     err.render(src, Some("config.hjson")),
+    ///     r#"error: b: expected a colon after the map key
+    ///  --> config.hjson:3:5
+    ///   |
+    /// 2 |   a: 1
+    /// 3 |   b 2
+    ///   |     ^
+    /// 4 | }
+    /// "#,
+    /// );
+    /// ```
+    pub fn render(&self, src: &str, file_name: Option<&str>) -> String {
+        use std::fmt::Write;
+        let mut s = String::new();
+        let message = match self {
+            Self::Syntax { code, .. } => code.to_string(),
+            Self::Serde { message, .. } => message.to_string(),
+            Self::RawSerde(message) => message.to_string(),
+            _ => self.to_string(),
+        };
+        match self.path() {
+            Some(path) if !path.is_empty() => {
+                let _ = writeln!(s, "error: {}: {}", path, message);
+            }
+            _ => {
+                let _ = writeln!(s, "error: {}", message);
+            }
+        }
+        let (line, col) = match self.location() {
+            Some(location) => location,
+            None => {
+                if let Some(file_name) = file_name {
+                    let _ = writeln!(s, " --> {}", file_name);
+                }
+                return s;
+            }
+        };
+        let lines: Vec<&str> = src.lines().collect();
+        let first = line.saturating_sub(1).max(1);
+        let last = (line + 1).min(lines.len()).max(line);
+        let margin = last.to_string().len();
+        let _ = writeln!(
+            s,
+            "{:margin$}--> {}:{}:{}",
+            "",
+            file_name.unwrap_or("<input>"),
+            line,
+            col,
+            margin = margin,
+        );
+        let _ = writeln!(s, "{:margin$} |", "", margin = margin);
+        for l in first..=last {
+            let text = lines.get(l - 1).copied().unwrap_or("");
+            if text.is_empty() {
+                let _ = writeln!(s, "{:>margin$} |", l, margin = margin);
+            } else {
+                let _ = writeln!(s, "{:>margin$} | {}", l, text, margin = margin);
+            }
+            if l == line {
+                // we keep the tabs so that the caret is aligned
+                // whatever the tab width of the terminal
+                let padding: String = text
+                    .chars()
+                    .take(col - 1)
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                let _ = writeln!(s, "{:margin$} | {}^", "", padding, margin = margin);
+            }
+        }
+        s
+    }
 }
 
 impl de::Error for Error {
@@ -88,6 +262,12 @@ impl de::Error for Error {
     }
 }
 
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::RawSerde(msg.to_string())
+    }
+}
+
 impl From<Utf8Error> for Error {
     fn from(source: Utf8Error) -> Self {
         Self::Utf8(source)
@@ -103,10 +283,16 @@ impl From<io::Error> for Error {
 impl fmt::Display for Error {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Syntax { line, col, code, at } => {
+            Self::Syntax { line, col, code, at, path } => {
+                if !path.is_empty() {
+                    write!(formatter, "{}: ", path)?;
+                }
                 write!(formatter, "{:?} at {}:{} at {:?}", code, line, col, at)
             }
-            Self::Serde { line, col, message } => {
+            Self::Serde { line, col, message, path } => {
+                if !path.is_empty() {
+                    write!(formatter, "{}: ", path)?;
+                }
                 write!(formatter, "{:?} near {}:{}", message, line, col)
             }
             Self::RawSerde(msg) => {
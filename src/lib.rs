@@ -61,14 +61,39 @@ mod de;
 mod de_enum;
 mod de_map;
 mod de_number;
+mod de_reader;
 mod de_seq;
+mod de_spanned;
+mod de_stream;
+mod de_transcode;
+mod de_validate;
+pub mod edit;
 mod error;
+mod options;
+mod path;
+mod ser;
+mod spanned;
 mod utf8;
+mod value;
+mod value_de;
+mod value_ser;
 
-pub use error::*;
+pub use {
+    de::Deserializer,
+    de_stream::StreamDeserializer,
+    error::*,
+    options::*,
+    path::{ParsePathError, Path, PathSegment},
+    ser::Serializer,
+    spanned::Spanned,
+    value::{Map, Number, Value, ValueIndex},
+};
 
 /// Deserialize an instance of type `T` from a reader of Hjson text
 ///
+/// The text is read and parsed by chunks of bounded size, without
+/// keeping the whole of it in memory, even when it's on one line.
+///
 /// # Example
 ///
 /// ```
@@ -90,14 +115,22 @@ pub use error::*;
 /// let u: User = deser_hjson::from_reader(j).unwrap();
 /// println!("{:#?}", u);
 /// ```
-pub fn from_reader<R, T>(mut reader: R) -> Result<T>
+pub fn from_reader<R, T>(reader: R) -> Result<T>
 where
     R: std::io::Read,
     T: serde::de::DeserializeOwned,
 {
-    let mut buf = Vec::new();
-    reader.read_to_end(&mut buf)?;
-    from_slice(&buf)
+    let mut deserializer = de::Deserializer::from_reader(std::io::BufReader::new(reader));
+    let t = T::deserialize(&mut deserializer)
+        .or_else(|e| deserializer.cook_err(e));
+    // when the reading failed, the input was truncated, which
+    // is probably the cause of a deserialization error
+    if let Some(e) = deserializer.take_read_error() {
+        return Err(e);
+    }
+    let t = t?;
+    deserializer.check_all_consumed()?;
+    Ok(t)
 }
 
 
@@ -159,7 +192,348 @@ where
     T: serde::de::DeserializeOwned,
 {
     let mut deserializer = de::Deserializer::from_str(s);
-    let t = T::deserialize(&mut deserializer)?;
+    let t = T::deserialize(&mut deserializer)
+        .or_else(|e| deserializer.cook_err(e))?;
+    deserializer.check_all_consumed()?;
+    Ok(t)
+}
+
+/// Deserialize an instance of type `T`, which may borrow from the
+/// source, from a string of Hjson text
+///
+/// Strings are borrowed from the source when they're written
+/// without escape sequence and aren't multiline strings. Other
+/// strings can't be deserialized into `&str` but may be deserialized
+/// into `Cow<str>` (with `#[serde(borrow)]` in a struct).
+///
+/// # Example
+///
+/// ```
+/// use {
+///     serde::Deserialize,
+///     std::borrow::Cow,
+/// };
+///
+/// #[derive(Deserialize, Debug)]
+/// struct User<'a> {
+///     name: &'a str,
+///     #[serde(borrow)]
+///     location: Cow<'a, str>,
+///     #[serde(borrow)]
+///     motto: Cow<'a, str>,
+/// }
+///
+/// let j = r#"
+///     name: "Alice"
+///     location: Menlo Park, CA
+///     motto: "Escape \"sequences\" need allocations"
+/// "#;
+///
+/// let u: User = deser_hjson::from_str_borrowed(j).unwrap();
+/// assert_eq!(u.name, "Alice");
+/// assert!(matches!(u.location, Cow::Borrowed("Menlo Park, CA")));
+/// assert!(matches!(u.motto, Cow::Owned(_)));
+/// ```
+pub fn from_str_borrowed<'de, T>(s: &'de str) -> Result<T>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    let mut deserializer = de::Deserializer::from_str(s);
+    let t = T::deserialize(&mut deserializer)
+        .or_else(|e| deserializer.cook_err(e))?;
     deserializer.check_all_consumed()?;
     Ok(t)
 }
+
+/// Deserialize an instance of type `T` from a string of Hjson text,
+/// with specific parsing options
+///
+/// # Example
+///
+/// ```
+/// use deser_hjson::*;
+///
+/// let options = DeserializerOptions::new().quoteless_values(false);
+/// let v: Vec<String> = from_str_with_options(r#"["a", "b"]"#, &options).unwrap();
+/// assert_eq!(v, vec!["a", "b"]);
+/// assert!(from_str_with_options::<Vec<String>>("[\na\nb\n]", &options).is_err());
+/// ```
+pub fn from_str_with_options<T>(s: &str, options: &DeserializerOptions) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let mut deserializer = de::Deserializer::with_options(s, options.clone());
+    let t = T::deserialize(&mut deserializer)
+        .or_else(|e| deserializer.cook_err(e))?;
+    deserializer.check_all_consumed()?;
+    Ok(t)
+}
+
+/// Deserialize an instance of type `T` from the start of a string,
+/// returning it with the position in bytes where it ended, so that
+/// the rest of the string can be read another way
+///
+/// A braceless root map ends at a `---` line, which is consumed (as
+/// is a `---` line right after any other value). When the string
+/// starts with a `---` line, it's skipped, as is usual for front matter.
+///
+/// # Example
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Debug)]
+/// struct FrontMatter {
+///     title: String,
+///     tags: Vec<String>,
+/// }
+///
+/// let page = r#"
+/// ---
+/// title: Some Title
+/// tags: [ "hjson", "rust" ]
+/// ---
+/// Some *Markdown* content
+/// "#;
+/// let (front_matter, end): (FrontMatter, usize) = deser_hjson::from_str_prefix(page).unwrap();
+/// assert_eq!(front_matter.title, "Some Title");
+/// assert_eq!(&page[end..], "Some *Markdown* content\n");
+/// ```
+pub fn from_str_prefix<'de, T>(s: &'de str) -> Result<(T, usize)>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    let options = DeserializerOptions::new().root_delimiter("---");
+    from_str_prefix_with_options(s, &options)
+}
+
+/// Deserialize an instance of type `T` from the start of a string,
+/// with specific parsing options, returning it with the position
+/// in bytes where it ended
+///
+/// The root delimiter of the options, if any, plays the role of
+/// the `---` line in [from_str_prefix].
+///
+/// # Example
+///
+/// ```
+/// use deser_hjson::*;
+///
+/// let options = DeserializerOptions::new().root_delimiter("+++");
+/// let text = "a: 1\nb: 2\n+++\nsomething else";
+/// let (map, end): (Value, usize) = from_str_prefix_with_options(text, &options).unwrap();
+/// assert_eq!(map["b"], Value::from(2u64));
+/// assert_eq!(&text[end..], "something else");
+/// let (v, end): (Vec<u8>, usize) = from_str_prefix("[1, 2] and the rest").unwrap();
+/// assert_eq!(v, vec![1, 2]);
+/// assert_eq!(end, 6);
+/// ```
+pub fn from_str_prefix_with_options<'de, T>(
+    s: &'de str,
+    options: &DeserializerOptions,
+) -> Result<(T, usize)>
+where
+    T: serde::de::Deserialize<'de>,
+{
+    let mut deserializer = de::Deserializer::with_options(s, options.clone());
+    deserializer.skip_opening_root_delimiter();
+    let t = T::deserialize(&mut deserializer)
+        .or_else(|e| deserializer.cook_err(e))?;
+    Ok((t, deserializer.prefix_end()))
+}
+
+/// Check a string of Hjson text and return all the syntax errors
+/// found in it, instead of stopping at the first one
+///
+/// After an error, the check goes on from the next line or closing
+/// bracket, so some errors may be consequences of the previous ones.
+///
+/// # Example
+///
+/// ```
+/// let hjson = r#"
+///     name: Alice
+///     age 34
+///     location: "Menlo Park\q, CA"
+///     langs: [
+///         Rust
+///         { level: }
+///     ]
+/// "#;
+/// let errors = deser_hjson::validate_str(hjson);
+/// let lines: Vec<usize> = errors.iter()
+///     .filter_map(|e| e.location())
+///     .map(|(line, _)| line)
+///     .collect();
+/// assert_eq!(lines, vec![3, 4, 7]);
+/// assert!(deser_hjson::validate_str("a: 1").is_empty());
+/// ```
+pub fn validate_str(s: &str) -> Vec<Error> {
+    de::Deserializer::from_str(s).validate()
+}
+
+/// Convert a Hjson document into compact JSON, written into the writer.
+///
+/// No intermediate value is built, numbers are written exactly as in
+/// the Hjson text, and map entries keep their order.
+///
+/// As JSON can't represent them, the NaN and infinite floats read with
+/// the `non_finite_floats` option raise a `NonFiniteFloat` error.
+///
+/// ```
+/// let hjson = r#"
+/// // a comment
+/// name: Alice
+/// ratio: 1.50
+/// big: 123456789012345678901234567890
+/// tags: ["a", "b"]
+/// "#;
+/// let mut json = Vec::new();
+/// deser_hjson::transcode(hjson, &mut json).unwrap();
+/// assert_eq!(
+///     String::from_utf8(json).unwrap(),
+///     r#"{"name":"Alice","ratio":1.50,"big":123456789012345678901234567890,"tags":["a","b"]}"#,
+/// );
+/// ```
+pub fn transcode<W: std::io::Write>(hjson: &str, writer: W) -> Result<()> {
+    let mut deserializer = de::Deserializer::from_str(hjson);
+    deserializer.transcode(writer)?;
+    deserializer.check_all_consumed()
+}
+
+/// Convert a Hjson document into pretty JSON, indented with two spaces,
+/// written into the writer (see [transcode])
+pub fn transcode_pretty<W: std::io::Write>(hjson: &str, writer: W) -> Result<()> {
+    let mut deserializer = de::Deserializer::from_str(hjson);
+    deserializer.transcode_pretty(writer)?;
+    deserializer.check_all_consumed()
+}
+
+/// Deserialize an instance of type `T` from a [Value], for example
+/// after having checked or modified a dynamically read document
+///
+/// # Example
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Debug)]
+/// struct User {
+///     hands: Option<u16>,
+///     location: String,
+/// }
+///
+/// let mut value: deser_hjson::Value = deser_hjson::from_str("
+///     hands: 2
+///     location: Menlo Park, CA
+/// ").unwrap();
+/// value.as_map_mut().unwrap().remove("hands");
+/// let u: User = deser_hjson::from_value(value).unwrap();
+/// assert_eq!(u.hands, None);
+/// ```
+pub fn from_value<T>(value: Value) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    T::deserialize(value)
+}
+
+/// Serialize the given value as compact Hjson into the writer.
+///
+/// The output is a single line, with commas between entries
+/// and all strings quoted. Use [to_writer_pretty] for Hjson
+/// intended to be read and edited by humans.
+///
+/// NaN and infinite floats are written as `NaN`, `Infinity` and
+/// `-Infinity`, which are read back with the `non_finite_floats` option.
+pub fn to_writer<W, T>(writer: W, value: &T) -> Result<()>
+where
+    W: std::io::Write,
+    T: ?Sized + serde::Serialize,
+{
+    let mut serializer = ser::Serializer::new(writer);
+    value.serialize(&mut serializer)
+}
+
+/// Serialize the given value as human friendly Hjson into the writer
+pub fn to_writer_pretty<W, T>(writer: W, value: &T) -> Result<()>
+where
+    W: std::io::Write,
+    T: ?Sized + serde::Serialize,
+{
+    let mut serializer = ser::Serializer::pretty(writer);
+    value.serialize(&mut serializer)
+}
+
+/// Serialize the given value as a compact Hjson string
+///
+/// # Example
+///
+/// ```
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     hands: Option<u16>,
+///     location: String,
+/// }
+///
+/// let user = User {
+///     hands: Some(2),
+///     location: "Menlo Park, CA".to_string(),
+/// };
+/// let hjson = deser_hjson::to_string(&user).unwrap();
+/// assert_eq!(hjson, r#"{hands:2,location:"Menlo Park, CA"}"#);
+/// ```
+pub fn to_string<T>(value: &T) -> Result<String>
+where
+    T: ?Sized + serde::Serialize,
+{
+    let mut buf = Vec::new();
+    to_writer(&mut buf, value)?;
+    // the serializer only writes valid UTF8
+    Ok(String::from_utf8(buf).expect("valid UTF8"))
+}
+
+/// Serialize the given value as a human friendly Hjson string:
+/// one entry per line, without commas, indented with two spaces,
+/// with quoteless strings when it's safe and multiline strings
+/// for texts with newlines.
+///
+/// # Example
+///
+/// ```
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     hands: Option<u16>,
+///     location: String,
+///     bio: String,
+/// }
+///
+/// let user = User {
+///     hands: Some(2),
+///     location: "Menlo Park, CA".to_string(),
+///     bio: "born in Paris\nlives in CA".to_string(),
+/// };
+/// let hjson = deser_hjson::to_string_pretty(&user).unwrap();
+/// assert_eq!(hjson, r#"{
+///   hands: 2
+///   location: Menlo Park, CA
+///   bio:
+///     '''
+///     born in Paris
+///     lives in CA
+///     '''
+/// }"#);
+/// ```
+pub fn to_string_pretty<T>(value: &T) -> Result<String>
+where
+    T: ?Sized + serde::Serialize,
+{
+    let mut buf = Vec::new();
+    to_writer_pretty(&mut buf, value)?;
+    // the serializer only writes valid UTF8
+    Ok(String::from_utf8(buf).expect("valid UTF8"))
+}
@@ -0,0 +1,268 @@
+use std::fmt;
+
+/// What to do when there's something else than spaces and
+/// comments after the deserialized value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrailingChars {
+    /// raise a `TrailingCharacters` error
+    #[default]
+    Forbid,
+    /// ignore whatever comes after the value
+    Ignore,
+}
+
+/// What to do when a map key appears more than once in the same map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeys {
+    /// raise a `DuplicateKey` error
+    Error,
+    /// keep the first entry and skip the other ones
+    FirstWins,
+    /// give all entries to the visitor, so that a map keeps the
+    /// last value (a derived struct rejects the duplicate field)
+    #[default]
+    LastWins,
+    /// like `LastWins`, but record a `DuplicateKey` warning, to be
+    /// read with [Deserializer::warnings](crate::Deserializer::warnings)
+    Warn,
+}
+
+/// An extension of Hjson over JSON, which the parsing options may disallow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HjsonFeature {
+    /// `#`, `//` and `/* */` comments
+    Comments,
+    /// map keys and enum variants without quotes
+    QuotelessKeys,
+    /// string values without quotes
+    QuotelessValues,
+    /// strings between single quotes
+    SingleQuotes,
+    /// strings between triple single quotes
+    MultilineStrings,
+    /// map entries or array items separated by a newline instead of a comma
+    OmittedCommas,
+    /// a comma after the last map entry or array item
+    TrailingCommas,
+    /// a root map without braces
+    BracelessRoot,
+}
+
+impl fmt::Display for HjsonFeature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Comments => "comments",
+            Self::QuotelessKeys => "quoteless keys",
+            Self::QuotelessValues => "quoteless strings",
+            Self::SingleQuotes => "single quoted strings",
+            Self::MultilineStrings => "multiline strings",
+            Self::OmittedCommas => "missing commas",
+            Self::TrailingCommas => "trailing commas",
+            Self::BracelessRoot => "braceless root maps",
+        };
+        f.write_str(s)
+    }
+}
+
+/// The parsing options of a [Deserializer](crate::Deserializer).
+///
+/// The default options are the ones of the `from_str` function,
+/// and they're tuned for Hjson written by humans.
+///
+/// ```
+/// use {
+///     deser_hjson::*,
+///     std::collections::HashMap,
+/// };
+///
+/// let options = DeserializerOptions::new()
+///     .braceless_root(false)
+///     .trailing_chars(TrailingChars::Ignore);
+/// let v: Vec<u8> = from_str_with_options("[1, 2] and some garbage", &options).unwrap();
+/// assert_eq!(v, vec![1, 2]);
+/// assert!(from_str_with_options::<HashMap<String, u8>>("a: 1", &options).is_err());
+/// assert!(from_str_with_options::<HashMap<String, u8>>("{a: 1}", &options).is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeserializerOptions {
+    pub(crate) braceless_root: bool,
+    pub(crate) quoteless_values: bool,
+    pub(crate) quoteless_keys: bool,
+    pub(crate) comments: bool,
+    pub(crate) single_quotes: bool,
+    pub(crate) multiline_strings: bool,
+    pub(crate) omitted_commas: bool,
+    pub(crate) trailing_commas: bool,
+    pub(crate) trailing_chars: TrailingChars,
+    pub(crate) recursion_limit: Option<usize>,
+    pub(crate) root_delimiter: Option<String>,
+    pub(crate) duplicate_keys: DuplicateKeys,
+    pub(crate) extended_integers: bool,
+    pub(crate) non_finite_floats: bool,
+}
+
+impl Default for DeserializerOptions {
+    fn default() -> Self {
+        Self {
+            braceless_root: true,
+            quoteless_values: true,
+            quoteless_keys: true,
+            comments: true,
+            single_quotes: true,
+            multiline_strings: true,
+            omitted_commas: true,
+            trailing_commas: true,
+            trailing_chars: TrailingChars::Forbid,
+            recursion_limit: Some(128),
+            root_delimiter: None,
+            duplicate_keys: DuplicateKeys::LastWins,
+            extended_integers: false,
+            non_finite_floats: false,
+        }
+    }
+}
+
+impl DeserializerOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Build options disallowing all the extensions of Hjson over JSON,
+    /// so that only JSON is accepted
+    ///
+    /// ```
+    /// use deser_hjson::*;
+    ///
+    /// let options = DeserializerOptions::strict_json();
+    /// let v: Vec<String> = from_str_with_options(r#"["a", "b"]"#, &options).unwrap();
+    /// assert_eq!(v, vec!["a", "b"]);
+    /// let err = from_str_with_options::<Vec<String>>("['a', 'b']", &options).unwrap_err();
+    /// assert!(matches!(
+    ///     err,
+    ///     Error::Syntax { code: ErrorCode::Disallowed(HjsonFeature::SingleQuotes), .. },
+    /// ));
+    /// ```
+    pub fn strict_json() -> Self {
+        Self::default()
+            .braceless_root(false)
+            .quoteless_values(false)
+            .quoteless_keys(false)
+            .comments(false)
+            .single_quotes(false)
+            .multiline_strings(false)
+            .omitted_commas(false)
+            .trailing_commas(false)
+    }
+    /// Set whether the root map may be written without braces
+    /// (default: true)
+    pub fn braceless_root(mut self, allow: bool) -> Self {
+        self.braceless_root = allow;
+        self
+    }
+    /// Set whether string values may be written without quotes
+    /// (default: true). Map keys may still be quoteless.
+    pub fn quoteless_values(mut self, allow: bool) -> Self {
+        self.quoteless_values = allow;
+        self
+    }
+    /// Set whether map keys and enum variants may be written
+    /// without quotes (default: true)
+    pub fn quoteless_keys(mut self, allow: bool) -> Self {
+        self.quoteless_keys = allow;
+        self
+    }
+    /// Set whether `#`, `//` and `/* */` comments are allowed
+    /// (default: true)
+    pub fn comments(mut self, allow: bool) -> Self {
+        self.comments = allow;
+        self
+    }
+    /// Set whether strings may be written between single quotes
+    /// (default: true)
+    pub fn single_quotes(mut self, allow: bool) -> Self {
+        self.single_quotes = allow;
+        self
+    }
+    /// Set whether multiline strings, between `'''`, are allowed
+    /// (default: true)
+    pub fn multiline_strings(mut self, allow: bool) -> Self {
+        self.multiline_strings = allow;
+        self
+    }
+    /// Set whether map entries and array items may be separated by
+    /// a newline instead of a comma (default: true)
+    pub fn omitted_commas(mut self, allow: bool) -> Self {
+        self.omitted_commas = allow;
+        self
+    }
+    /// Set whether a comma may follow the last map entry or array
+    /// item (default: true)
+    pub fn trailing_commas(mut self, allow: bool) -> Self {
+        self.trailing_commas = allow;
+        self
+    }
+    /// Set what `check_all_consumed` does when there are
+    /// characters after the value (default: forbid them)
+    pub fn trailing_chars(mut self, policy: TrailingChars) -> Self {
+        self.trailing_chars = policy;
+        self
+    }
+    /// Set how deep maps and arrays may be nested before a
+    /// `RecursionLimitExceeded` error is raised (default: 128)
+    pub fn recursion_limit(mut self, limit: usize) -> Self {
+        self.recursion_limit = Some(limit);
+        self
+    }
+    /// Allow any depth of nested maps and arrays.
+    ///
+    /// Only do this for trusted input: a deeply nested document
+    /// would make the process overflow its stack and abort.
+    pub fn disable_recursion_limit(mut self) -> Self {
+        self.recursion_limit = None;
+        self
+    }
+    /// Set a line, for example `---`, which ends the braceless root
+    /// map, so that other content may follow (default: none)
+    pub fn root_delimiter<S: Into<String>>(mut self, delimiter: S) -> Self {
+        self.root_delimiter = Some(delimiter.into());
+        self
+    }
+    /// Set what happens when a key appears twice in a map
+    /// (default: the last value wins)
+    ///
+    /// Keys are compared once unquoted and unescaped, as strings, so
+    /// `"a"` and `"\u0061"` are the same key but `1` and `01` aren't,
+    /// even when they're read as numbers.
+    pub fn duplicate_keys(mut self, policy: DuplicateKeys) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+    /// Set whether integers may be written in hexadecimal, octal or
+    /// binary, with a `0x`, `0o` or `0b` prefix, and with `_` between
+    /// their digits (default: false, as it's not in the Hjson spec)
+    ///
+    /// ```
+    /// use deser_hjson::*;
+    ///
+    /// let options = DeserializerOptions::new().extended_integers(true);
+    /// let masks: Vec<u32> = from_str_with_options("[0xFF00, 0o17, 0b1010, 1_000_000]", &options).unwrap();
+    /// assert_eq!(masks, vec![0xFF00, 0o17, 0b1010, 1_000_000]);
+    /// ```
+    pub fn extended_integers(mut self, allow: bool) -> Self {
+        self.extended_integers = allow;
+        self
+    }
+    /// Set whether `NaN`, `Infinity` and `-Infinity`, alone on their
+    /// line, are floats (default: false, so that they're strings)
+    ///
+    /// ```
+    /// use deser_hjson::*;
+    ///
+    /// let options = DeserializerOptions::new().non_finite_floats(true);
+    /// let limits: Vec<f64> = from_str_with_options("[-Infinity, 0, Infinity]", &options).unwrap();
+    /// assert_eq!(limits, vec![f64::NEG_INFINITY, 0.0, f64::INFINITY]);
+    /// ```
+    pub fn non_finite_floats(mut self, allow: bool) -> Self {
+        self.non_finite_floats = allow;
+        self
+    }
+}
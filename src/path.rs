@@ -0,0 +1,215 @@
+use std::{
+    fmt,
+    str::FromStr,
+};
+
+/// The location of a value in a document, as a list of map keys and
+/// array indexes, for example `servers[2].listen.port`
+///
+/// Keys which aren't simple identifiers are written between quotes
+/// and brackets, for example `servers["main server"].port`.
+///
+/// ```
+/// use deser_hjson::*;
+///
+/// let path: Path = "servers[2].listen.port".parse().unwrap();
+/// assert_eq!(path.segments(), &[
+///     PathSegment::Key("servers".to_string()),
+///     PathSegment::Index(2),
+///     PathSegment::Key("listen".to_string()),
+///     PathSegment::Key("port".to_string()),
+/// ]);
+/// assert_eq!(path.to_string(), "servers[2].listen.port");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+/// A part of a path: a map key or an array index
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// The error returned when a string can't be parsed as a path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePathError {
+    pub pos: usize,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// whether the path points to the root of the document
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+    /// return the path of the parent value, if this path isn't empty
+    pub fn parent(&self) -> Option<Path> {
+        self.segments.split_last().map(|(_, segments)| Path {
+            segments: segments.to_vec(),
+        })
+    }
+    pub fn last(&self) -> Option<&PathSegment> {
+        self.segments.last()
+    }
+    pub fn iter(&self) -> std::slice::Iter<'_, PathSegment> {
+        self.segments.iter()
+    }
+}
+
+impl From<Vec<PathSegment>> for Path {
+    fn from(segments: Vec<PathSegment>) -> Self {
+        Self { segments }
+    }
+}
+
+impl<'p> IntoIterator for &'p Path {
+    type Item = &'p PathSegment;
+    type IntoIter = std::slice::Iter<'p, PathSegment>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.segments.iter()
+    }
+}
+
+/// tell whether the key can be written without quotes and brackets
+fn is_simple_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '$')
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Key(key) if is_simple_key(key) => f.write_str(key),
+            Self::Key(key) => {
+                f.write_str("[\"")?;
+                for c in key.chars() {
+                    match c {
+                        '"' => f.write_str("\\\"")?,
+                        '\\' => f.write_str("\\\\")?,
+                        '\n' => f.write_str("\\n")?,
+                        _ => write!(f, "{}", c)?,
+                    }
+                }
+                f.write_str("\"]")
+            }
+            Self::Index(idx) => write!(f, "[{}]", idx),
+        }
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                if let PathSegment::Key(key) = segment {
+                    if is_simple_key(key) {
+                        f.write_str(".")?;
+                    }
+                }
+            }
+            segment.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Path {
+    type Err = ParsePathError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut chars = s.char_indices().peekable();
+        let mut expect_segment = true; // at start or after a dot
+        while let Some((pos, c)) = chars.next() {
+            match c {
+                '[' => {
+                    match chars.peek() {
+                        Some(&(_, '"')) => {
+                            chars.next();
+                            let mut key = String::new();
+                            loop {
+                                match chars.next() {
+                                    Some((_, '"')) => break,
+                                    Some((_, '\\')) => match chars.next() {
+                                        Some((_, 'n')) => key.push('\n'),
+                                        Some((_, c)) => key.push(c),
+                                        None => return Err(ParsePathError { pos: s.len() }),
+                                    },
+                                    Some((_, c)) => key.push(c),
+                                    None => return Err(ParsePathError { pos: s.len() }),
+                                }
+                            }
+                            segments.push(PathSegment::Key(key));
+                        }
+                        _ => {
+                            let mut idx = String::new();
+                            while let Some(&(_, c)) = chars.peek() {
+                                if !c.is_ascii_digit() {
+                                    break;
+                                }
+                                idx.push(c);
+                                chars.next();
+                            }
+                            let idx = idx.parse().map_err(|_| ParsePathError { pos: pos + 1 })?;
+                            segments.push(PathSegment::Index(idx));
+                        }
+                    }
+                    match chars.next() {
+                        Some((_, ']')) => {}
+                        Some((pos, _)) => return Err(ParsePathError { pos }),
+                        None => return Err(ParsePathError { pos: s.len() }),
+                    }
+                    expect_segment = false;
+                }
+                '.' if !expect_segment => {
+                    expect_segment = true;
+                }
+                _ if expect_segment && c != '.' && c != ']' => {
+                    let mut key = c.to_string();
+                    while let Some(&(_, c)) = chars.peek() {
+                        if c == '.' || c == '[' {
+                            break;
+                        }
+                        key.push(c);
+                        chars.next();
+                    }
+                    segments.push(PathSegment::Key(key));
+                    expect_segment = false;
+                }
+                _ => {
+                    return Err(ParsePathError { pos });
+                }
+            }
+        }
+        if expect_segment && !segments.is_empty() {
+            // the path ends with a dot
+            return Err(ParsePathError { pos: s.len() });
+        }
+        Ok(Self { segments })
+    }
+}
+
+impl fmt::Display for ParsePathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid path (at byte {})", self.pos)
+    }
+}
+
+impl std::error::Error for ParsePathError {}
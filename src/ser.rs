@@ -0,0 +1,863 @@
+//! A Hjson serializer.
+//!
+use {
+    crate::{
+        de_number::NUMBER_TOKEN,
+        error::{Error, Result},
+    },
+    serde::ser::{self, Serialize},
+    std::io,
+};
+
+/// The serializer. You normally don't call it directly
+/// but use the `to_string`, `to_string_pretty` or `to_writer`
+/// functions available at crate's level.
+///
+/// There are two output styles:
+/// - compact: everything on one line, with commas and quoted strings
+/// - pretty: one entry per line, no comma, 2 spaces indentation,
+///   quoteless strings when it's safe, and `'''` multiline strings
+pub struct Serializer<W> {
+    writer: W,
+    pretty: bool,
+
+    // current nesting depth (used for indentation in pretty mode)
+    depth: usize,
+
+    // true when a map key and its colon have just been written,
+    // so that the value knows how to start
+    after_colon: bool,
+
+    // true when the coming string is the text of a number,
+    // to be written as is
+    raw_number: bool,
+}
+
+impl<W: io::Write> Serializer<W> {
+
+    /// build a serializer writing compact Hjson
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            pretty: false,
+            depth: 0,
+            after_colon: false,
+            raw_number: false,
+        }
+    }
+
+    /// build a serializer writing human friendly Hjson
+    pub fn pretty(writer: W) -> Self {
+        Self {
+            writer,
+            pretty: true,
+            depth: 0,
+            after_colon: false,
+            raw_number: false,
+        }
+    }
+
+    /// unwrap the writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    #[inline]
+    fn write(&mut self, s: &str) -> Result<()> {
+        self.writer.write_all(s.as_bytes())?;
+        Ok(())
+    }
+
+    /// in pretty mode, go to a new line and write the indentation
+    /// of the given depth
+    fn new_line(&mut self, depth: usize) -> Result<()> {
+        if self.pretty {
+            self.write("\n")?;
+            for _ in 0..depth {
+                self.write("  ")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// write what must come before a value on the same line
+    /// (i.e. a space after a map colon in pretty mode)
+    fn begin_value(&mut self) -> Result<()> {
+        if self.after_colon {
+            self.after_colon = false;
+            if self.pretty {
+                self.write(" ")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_value(&mut self, s: &str) -> Result<()> {
+        self.begin_value()?;
+        self.write(s)
+    }
+
+    fn write_quoted(&mut self, s: &str) -> Result<()> {
+        write_json_string(&mut self.writer, s)
+    }
+
+    /// write a multiline string, the `'''` being at the
+    /// indentation of the current depth
+    fn write_multiline(&mut self, s: &str) -> Result<()> {
+        let depth = if self.after_colon {
+            self.after_colon = false;
+            self.new_line(self.depth + 1)?;
+            self.depth + 1
+        } else {
+            self.depth
+        };
+        self.write("'''")?;
+        for line in s.split('\n') {
+            if line.is_empty() {
+                self.write("\n")?;
+            } else {
+                self.new_line(depth)?;
+                self.write(line)?;
+            }
+        }
+        self.new_line(depth)?;
+        self.write("'''")
+    }
+
+    fn write_str_value(&mut self, s: &str) -> Result<()> {
+        if self.pretty {
+            if is_multiline_compatible(s) {
+                return self.write_multiline(s);
+            }
+            // at the root, a string looking like a key would be read
+            // as a braceless map
+            if is_quoteless_value_compatible(s) && !(self.depth == 0 && looks_like_map_key(s)) {
+                return self.write_value(s);
+            }
+        }
+        self.begin_value()?;
+        self.write_quoted(s)
+    }
+
+    fn write_key(&mut self, s: &str) -> Result<()> {
+        if is_quoteless_key_compatible(s) {
+            self.write(s)
+        } else {
+            self.write_quoted(s)
+        }
+    }
+
+    /// write a float, NaN and infinites being written as they're
+    /// read with the `non_finite_floats` option
+    fn write_float(&mut self, v: f64) -> Result<()> {
+        if v.is_finite() {
+            let mut s = v.to_string();
+            if !s.contains(['.', 'e', 'E']) {
+                // we don't want the float to be read back as an integer
+                s.push_str(".0");
+            }
+            self.write_value(&s)
+        } else if v.is_nan() {
+            self.write_value("NaN")
+        } else if v > 0.0 {
+            self.write_value("Infinity")
+        } else {
+            self.write_value("-Infinity")
+        }
+    }
+
+    /// open a map with a single key, used for enum variants
+    /// holding data
+    fn begin_variant(&mut self, variant: &str) -> Result<()> {
+        self.write_value("{")?;
+        self.depth += 1;
+        self.new_line(self.depth)?;
+        self.write_key(variant)?;
+        self.write(":")?;
+        self.after_colon = true;
+        Ok(())
+    }
+
+    fn end_variant(&mut self) -> Result<()> {
+        self.depth -= 1;
+        self.new_line(self.depth)?;
+        self.write("}")
+    }
+}
+
+/// write a string between double quotes, with the escapes
+/// of JSON, which are also the ones of Hjson
+pub(crate) fn write_json_string<W: io::Write>(writer: &mut W, s: &str) -> Result<()> {
+    writer.write_all(b"\"")?;
+    let mut start = 0;
+    for (idx, ch) in s.char_indices() {
+        let escaped = match ch {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            '\t' => "\\t",
+            '\x08' => "\\b",
+            '\x0C' => "\\f",
+            _ if ch.is_control() => "",
+            _ => continue,
+        };
+        writer.write_all(&s.as_bytes()[start..idx])?;
+        if escaped.is_empty() {
+            write!(writer, "\\u{:04x}", ch as u32)?;
+        } else {
+            writer.write_all(escaped.as_bytes())?;
+        }
+        start = idx + ch.len_utf8();
+    }
+    writer.write_all(&s.as_bytes()[start..])?;
+    writer.write_all(b"\"")?;
+    Ok(())
+}
+
+/// tell whether a string can be written as a quoteless map key
+pub(crate) fn is_quoteless_key_compatible(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with(['"', '\'', '#'])
+        && !s.starts_with("//")
+        && !s.starts_with("/*")
+        && !s.contains(|c: char| {
+            matches!(c, ',' | ':' | '[' | ']' | '{' | '}') || c.is_whitespace() || c.is_control()
+        })
+}
+
+/// tell whether a string can be written as a quoteless value
+/// and read back as the same string, even when the type isn't
+/// known at deserialization
+fn is_quoteless_value_compatible(s: &str) -> bool {
+    let first = match s.chars().next() {
+        Some(c) => c,
+        None => {
+            return false;
+        }
+    };
+    if first.is_whitespace() || s.ends_with(char::is_whitespace) {
+        return false;
+    }
+    // a leading '*' would be skipped like a comment char
+    if matches!(first, '"' | '\'' | '#' | '*' | ',' | ':' | '[' | ']' | '{' | '}' | '-' | '0'..='9') {
+        return false;
+    }
+    if s.starts_with("//") || s.starts_with("/*") {
+        return false;
+    }
+    if s.starts_with("true") || s.starts_with("false") || s.starts_with("null") {
+        return false;
+    }
+    // they would be read as floats with the `non_finite_floats` option
+    if s.starts_with("NaN") || s.starts_with("Infinity") {
+        return false;
+    }
+    !s.contains(|c: char| c.is_control() && c != '\t')
+}
+
+/// tell whether a quoteless string starts with what would be read
+/// as a map key followed by its colon
+fn looks_like_map_key(s: &str) -> bool {
+    s.find([',', '[', ']', '{', '}', ':', ' ', '\t'])
+        .filter(|&idx| idx > 0)
+        .map(|idx| s[idx..].trim_start_matches([' ', '\t']).starts_with(':'))
+        .unwrap_or(false)
+}
+
+/// tell whether a string is better written as a multiline
+/// string and can be read back as the same string
+fn is_multiline_compatible(s: &str) -> bool {
+    s.contains('\n')
+        && !s.ends_with('\n')
+        && !s.contains("'''")
+        && !s.contains(|c: char| c.is_control() && c != '\n' && c != '\t')
+        && !s.lines().any(|line| line.ends_with(char::is_whitespace))
+}
+
+impl<'a, W: io::Write> ser::Serializer for &'a mut Serializer<W> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Compound<'a, W>;
+    type SerializeTuple = Compound<'a, W>;
+    type SerializeTupleStruct = Compound<'a, W>;
+    type SerializeTupleVariant = Compound<'a, W>;
+    type SerializeMap = Compound<'a, W>;
+    type SerializeStruct = Compound<'a, W>;
+    type SerializeStructVariant = Compound<'a, W>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.write_value(if v { "true" } else { "false" })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.write_value(&v.to_string())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.write_value(&v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.write_value(&v.to_string())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.write_value(&v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        if v.is_finite() {
+            // going through the f32 string representation avoids
+            // writing 0.1f32 as 0.10000000149011612
+            let s = v.to_string();
+            self.write_float(s.parse().unwrap_or(f64::from(v)))
+        } else {
+            self.write_float(f64::from(v))
+        }
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.write_float(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.write_str_value(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        if self.raw_number {
+            self.raw_number = false;
+            return self.write_value(v);
+        }
+        self.write_str_value(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        use ser::SerializeSeq;
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            seq.serialize_element(byte)?;
+        }
+        seq.end()
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.write_value("null")
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.begin_variant(variant)?;
+        value.serialize(&mut *self)?;
+        self.end_variant()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        self.write_value("[")?;
+        self.depth += 1;
+        Ok(Compound::new(self, false))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        self.begin_variant(variant)?;
+        self.write_value("[")?;
+        self.depth += 1;
+        Ok(Compound::new(self, true))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        self.write_value("{")?;
+        self.depth += 1;
+        Ok(Compound::new(self, false))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct> {
+        if name == NUMBER_TOKEN {
+            // a number given with its exact text, whose only
+            // field is written without quotes
+            self.raw_number = true;
+            let mut number = Compound::new(self, false);
+            number.raw_number = true;
+            return Ok(number);
+        }
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        self.begin_variant(variant)?;
+        self.write_value("{")?;
+        self.depth += 1;
+        Ok(Compound::new(self, true))
+    }
+}
+
+/// The state of a map or sequence being serialized
+pub struct Compound<'a, W> {
+    ser: &'a mut Serializer<W>,
+    /// whether no entry has been written yet
+    empty: bool,
+    /// whether the compound is wrapped in a `{ variant: ... }` map
+    in_variant: bool,
+    /// whether it's not a real struct but the text of a number
+    raw_number: bool,
+}
+
+impl<'a, W: io::Write> Compound<'a, W> {
+    fn new(ser: &'a mut Serializer<W>, in_variant: bool) -> Self {
+        Self {
+            ser,
+            empty: true,
+            in_variant,
+            raw_number: false,
+        }
+    }
+
+    /// go to the line of a new entry (or write the comma separating
+    /// it from the previous one in compact mode)
+    fn begin_entry(&mut self) -> Result<()> {
+        if self.ser.pretty {
+            self.ser.new_line(self.ser.depth)?;
+        } else if !self.empty {
+            self.ser.write(",")?;
+        }
+        self.empty = false;
+        Ok(())
+    }
+
+    fn close(self, closing: &str) -> Result<()> {
+        self.ser.depth -= 1;
+        if !self.empty {
+            self.ser.new_line(self.ser.depth)?;
+        }
+        self.ser.write(closing)?;
+        if self.in_variant {
+            self.ser.end_variant()?;
+        }
+        Ok(())
+    }
+
+    fn element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.begin_entry()?;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn field<T>(&mut self, key: &str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.begin_entry()?;
+        self.ser.write_key(key)?;
+        self.ser.write(":")?;
+        self.ser.after_colon = true;
+        value.serialize(&mut *self.ser)
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeSeq for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.close("]")
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeTuple for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.close("]")
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeTupleStruct for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.close("]")
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeTupleVariant for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.close("]")
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeMap for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.begin_entry()?;
+        key.serialize(MapKeySerializer { ser: &mut *self.ser })?;
+        self.ser.write(":")?;
+        self.ser.after_colon = true;
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<()> {
+        self.close("}")
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeStruct for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        if self.raw_number {
+            return value.serialize(&mut *self.ser);
+        }
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        if self.raw_number {
+            self.ser.raw_number = false;
+            return Ok(());
+        }
+        self.close("}")
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeStructVariant for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.close("}")
+    }
+}
+
+/// A serializer for map keys, which must be strings (or
+/// primitives which are written as their string representation)
+struct MapKeySerializer<'a, W> {
+    ser: &'a mut Serializer<W>,
+}
+
+fn key_must_be_a_string() -> Error {
+    ser::Error::custom("map key must be a string")
+}
+
+impl<'a, W: io::Write> ser::Serializer for MapKeySerializer<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<(), Error>;
+    type SerializeTuple = ser::Impossible<(), Error>;
+    type SerializeTupleStruct = ser::Impossible<(), Error>;
+    type SerializeTupleVariant = ser::Impossible<(), Error>;
+    type SerializeMap = ser::Impossible<(), Error>;
+    type SerializeStruct = ser::Impossible<(), Error>;
+    type SerializeStructVariant = ser::Impossible<(), Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.ser.write(if v { "true" } else { "false" })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.ser.write(&v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.ser.write(&v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.ser.write(&v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.ser.write(&v.to_string())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.ser.write(&v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.ser.write(&v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.ser.write(&v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.ser.write(&v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.ser.write(&v.to_string())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.ser.write(&v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<()> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<()> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.ser.write_key(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.ser.write_key(v)
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<()> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.ser.write_key(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(key_must_be_a_string())
+    }
+}
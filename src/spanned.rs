@@ -0,0 +1,164 @@
+use {
+    serde::{
+        de::{self, Deserialize, MapAccess, Visitor},
+        ser::{Serialize, Serializer},
+    },
+    std::{
+        fmt,
+        ops::Range,
+    },
+};
+
+// the magic names used to recognize a Spanned in the deserializer
+pub(crate) const NAME: &str = "$__deser_hjson_private_Spanned";
+pub(crate) const START_FIELD: &str = "$__deser_hjson_private_start";
+pub(crate) const END_FIELD: &str = "$__deser_hjson_private_end";
+pub(crate) const LINE_FIELD: &str = "$__deser_hjson_private_line";
+pub(crate) const COL_FIELD: &str = "$__deser_hjson_private_col";
+pub(crate) const VALUE_FIELD: &str = "$__deser_hjson_private_value";
+pub(crate) const FIELDS: &[&str] = &[
+    START_FIELD,
+    END_FIELD,
+    LINE_FIELD,
+    COL_FIELD,
+    VALUE_FIELD,
+];
+
+/// A deserialized value with the location where it
+/// was found in the Hjson source.
+///
+/// This lets you point the user at the precise place of a
+/// value which is syntactically correct but semantically wrong.
+///
+/// A `Spanned` can only be deserialized with this crate's
+/// deserializer. It's serialized as the inner value.
+///
+/// ```
+/// use {
+///     deser_hjson::*,
+///     serde::Deserialize,
+/// };
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     name: String,
+///     port: Spanned<u16>,
+/// }
+///
+/// let hjson = "
+/// name: my server
+/// port: 80
+/// ";
+/// let config: Config = from_str(hjson).unwrap();
+/// assert_eq!(*config.port.get_ref(), 80);
+/// assert_eq!(config.port.line(), 3);
+/// assert_eq!(config.port.col(), 7);
+/// assert_eq!(&hjson[config.port.span()], "80");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Spanned<T> {
+    start: usize,
+    end: usize,
+    line: usize,
+    col: usize,
+    value: T,
+}
+
+impl<T> Spanned<T> {
+    /// the byte offset of the start of the value in the source
+    pub fn start(&self) -> usize {
+        self.start
+    }
+    /// the byte offset of the end of the value in the source
+    /// (trailing spaces excluded)
+    pub fn end(&self) -> usize {
+        self.end
+    }
+    /// the byte range of the value in the source
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+    /// the line of the start of the value (first line is 1)
+    pub fn line(&self) -> usize {
+        self.line
+    }
+    /// the column, in chars, of the start of the value (first column is 1)
+    pub fn col(&self) -> usize {
+        self.col
+    }
+    pub fn get_ref(&self) -> &T {
+        &self.value
+    }
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+/// Spanned values are compared on their values only
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Spanned<T> {}
+
+impl<T: Serialize> Serialize for Spanned<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Spanned<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct SpannedVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for SpannedVisitor<T> {
+            type Value = Spanned<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a spanned value")
+            }
+
+            fn visit_map<A>(self, mut access: A) -> Result<Spanned<T>, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut start = None;
+                let mut end = None;
+                let mut line = None;
+                let mut col = None;
+                let mut value = None;
+                while let Some(key) = access.next_key::<&str>()? {
+                    match key {
+                        START_FIELD => start = Some(access.next_value()?),
+                        END_FIELD => end = Some(access.next_value()?),
+                        LINE_FIELD => line = Some(access.next_value()?),
+                        COL_FIELD => col = Some(access.next_value()?),
+                        VALUE_FIELD => value = Some(access.next_value()?),
+                        _ => {
+                            return Err(de::Error::custom("not a spanned value"));
+                        }
+                    }
+                }
+                match (start, end, line, col, value) {
+                    (Some(start), Some(end), Some(line), Some(col), Some(value)) => {
+                        Ok(Spanned { start, end, line, col, value })
+                    }
+                    _ => Err(de::Error::custom("not a spanned value")),
+                }
+            }
+        }
+
+        deserializer.deserialize_struct(NAME, FIELDS, SpannedVisitor(std::marker::PhantomData))
+    }
+}
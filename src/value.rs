@@ -0,0 +1,509 @@
+//! A dynamically typed Hjson value, for documents whose
+//! structure isn't known in advance
+//!
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt,
+    iter::FromIterator,
+    ops,
+};
+
+/// Any Hjson value
+///
+/// It's deserialized with the type guessing of `deserialize_any`,
+/// so, as Hjson is ambiguous, `3` is a number and `"3"` a string.
+///
+/// ```
+/// use deser_hjson::Value;
+///
+/// let value: Value = deser_hjson::from_str("
+///     name: Alice
+///     pets: [
+///         {
+///             kind: cat
+///             age: 3
+///         }
+///     ]
+/// ").unwrap();
+/// assert_eq!(value["name"].as_str(), Some("Alice"));
+/// assert_eq!(value["pets"][0]["age"].as_u64(), Some(3));
+/// assert!(value["pets"][1]["age"].is_null());
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Value {
+    #[default]
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Value>),
+    Map(Map),
+}
+
+/// A Hjson number, which may be an integer (of up to 128 bits) or a float
+///
+/// With the `arbitrary_precision` feature, the numbers which aren't
+/// 128 bits integers keep the text they were read from, so that they
+/// can be written back or deserialized without loss.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(feature = "arbitrary_precision"), derive(Copy))]
+pub struct Number {
+    n: N,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(feature = "arbitrary_precision"), derive(Copy))]
+enum N {
+    PosInt(u64),
+    /// always less than zero
+    NegInt(i64),
+    /// always greater than `u64::MAX`
+    PosBigInt(u128),
+    /// always less than `i64::MIN`
+    NegBigInt(i128),
+    Float(f64),
+    /// the text of a number which isn't a 128 bits integer
+    #[cfg(feature = "arbitrary_precision")]
+    Raw(String),
+}
+
+/// A map of string keys to Hjson values, keeping the order
+/// in which the entries were inserted
+///
+/// Looking for a key doesn't depend on the size of the map.
+#[derive(Clone, Default)]
+pub struct Map {
+    entries: Vec<(String, Value)>,
+    /// the position of each key in `entries`
+    index: HashMap<String, usize>,
+}
+
+static NULL: Value = Value::Null;
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            Self::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(Number::as_u64)
+    }
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(Number::as_i64)
+    }
+    pub fn as_u128(&self) -> Option<u128> {
+        self.as_number().and_then(Number::as_u128)
+    }
+    pub fn as_i128(&self) -> Option<i128> {
+        self.as_number().and_then(Number::as_i128)
+    }
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(Number::as_f64)
+    }
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Self::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Self::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+    pub fn as_map(&self) -> Option<&Map> {
+        match self {
+            Self::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+    pub fn as_map_mut(&mut self) -> Option<&mut Map> {
+        match self {
+            Self::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+    /// get the value at the given index (a key for a map,
+    /// a position for an array), if any
+    pub fn get<I: ValueIndex>(&self, index: I) -> Option<&Value> {
+        index.index_into(self)
+    }
+    pub fn get_mut<I: ValueIndex>(&mut self, index: I) -> Option<&mut Value> {
+        index.index_into_mut(self)
+    }
+    /// take the value, leaving a null in its place
+    pub fn take(&mut self) -> Value {
+        std::mem::take(self)
+    }
+}
+
+impl Number {
+    pub fn is_u64(&self) -> bool {
+        matches!(self.n, N::PosInt(_))
+    }
+    pub fn is_i64(&self) -> bool {
+        match self.n {
+            N::PosInt(v) => v <= i64::MAX as u64,
+            N::NegInt(_) => true,
+            _ => false,
+        }
+    }
+    pub fn is_u128(&self) -> bool {
+        matches!(self.n, N::PosInt(_) | N::PosBigInt(_))
+    }
+    pub fn is_i128(&self) -> bool {
+        match self.n {
+            N::PosBigInt(v) => v <= i128::MAX as u128,
+            N::PosInt(_) | N::NegInt(_) | N::NegBigInt(_) => true,
+            _ => false,
+        }
+    }
+    pub fn is_f64(&self) -> bool {
+        !matches!(
+            self.n,
+            N::PosInt(_) | N::NegInt(_) | N::PosBigInt(_) | N::NegBigInt(_)
+        )
+    }
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.n {
+            N::PosInt(v) => Some(v),
+            _ => None,
+        }
+    }
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.n {
+            N::PosInt(v) => i64::try_from(v).ok(),
+            N::NegInt(v) => Some(v),
+            _ => None,
+        }
+    }
+    pub fn as_u128(&self) -> Option<u128> {
+        match self.n {
+            N::PosInt(v) => Some(v as u128),
+            N::PosBigInt(v) => Some(v),
+            _ => None,
+        }
+    }
+    pub fn as_i128(&self) -> Option<i128> {
+        match self.n {
+            N::PosInt(v) => Some(v as i128),
+            N::NegInt(v) => Some(v as i128),
+            N::PosBigInt(v) => i128::try_from(v).ok(),
+            N::NegBigInt(v) => Some(v),
+            _ => None,
+        }
+    }
+    /// return the number as a float, which may be
+    /// a lossy conversion for big integers
+    pub fn as_f64(&self) -> f64 {
+        match &self.n {
+            N::PosInt(v) => *v as f64,
+            N::NegInt(v) => *v as f64,
+            N::PosBigInt(v) => *v as f64,
+            N::NegBigInt(v) => *v as f64,
+            N::Float(v) => *v,
+            #[cfg(feature = "arbitrary_precision")]
+            N::Raw(s) => s.parse().unwrap_or(f64::NAN),
+        }
+    }
+    /// build a number from its text, which must be a valid
+    /// JSON number, keeping it as is unless it's a 128 bits integer
+    #[cfg(feature = "arbitrary_precision")]
+    pub(crate) fn from_raw(s: String) -> Self {
+        if let Ok(v) = s.parse::<u128>() {
+            v.into()
+        } else if let Ok(v) = s.parse::<i128>() {
+            v.into()
+        } else {
+            Self { n: N::Raw(s) }
+        }
+    }
+    /// the text of the number, when it was read from a
+    /// source and isn't a 128 bits integer
+    #[cfg(feature = "arbitrary_precision")]
+    pub fn as_raw_str(&self) -> Option<&str> {
+        match &self.n {
+            N::Raw(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl From<u64> for Number {
+    fn from(v: u64) -> Self {
+        Self { n: N::PosInt(v) }
+    }
+}
+impl From<i64> for Number {
+    fn from(v: i64) -> Self {
+        if v < 0 {
+            Self { n: N::NegInt(v) }
+        } else {
+            Self { n: N::PosInt(v as u64) }
+        }
+    }
+}
+impl From<u128> for Number {
+    fn from(v: u128) -> Self {
+        match u64::try_from(v) {
+            Ok(v) => v.into(),
+            Err(_) => Self { n: N::PosBigInt(v) },
+        }
+    }
+}
+impl From<i128> for Number {
+    fn from(v: i128) -> Self {
+        if v < 0 {
+            match i64::try_from(v) {
+                Ok(v) => v.into(),
+                Err(_) => Self { n: N::NegBigInt(v) },
+            }
+        } else {
+            (v as u128).into()
+        }
+    }
+}
+impl From<f64> for Number {
+    fn from(v: f64) -> Self {
+        Self { n: N::Float(v) }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.n {
+            N::PosInt(v) => v.fmt(f),
+            N::NegInt(v) => v.fmt(f),
+            N::PosBigInt(v) => v.fmt(f),
+            N::NegBigInt(v) => v.fmt(f),
+            N::Float(v) => v.fmt(f),
+            #[cfg(feature = "arbitrary_precision")]
+            N::Raw(s) => s.fmt(f),
+        }
+    }
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.index.get(key).map(|&idx| &self.entries[idx].1)
+    }
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        let idx = *self.index.get(key)?;
+        Some(&mut self.entries[idx].1)
+    }
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+    /// insert an entry, returning the previous value for the key, if any.
+    ///
+    /// A new key is inserted at the end while an existing one keeps
+    /// its position.
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        match self.get_mut(&key) {
+            Some(old) => Some(std::mem::replace(old, value)),
+            None => {
+                self.index.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+    /// remove an entry, keeping the order of the other ones
+    /// (which makes it slower than the other operations)
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let idx = self.index.remove(key)?;
+        let (_, value) = self.entries.remove(idx);
+        for (k, _) in &self.entries[idx..] {
+            if let Some(i) = self.index.get_mut(k) {
+                *i -= 1;
+            }
+        }
+        Some(value)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&String, &mut Value)> {
+        self.entries.iter_mut().map(|(k, v)| (&*k, v))
+    }
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+impl fmt::Debug for Map {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Map")
+            .field("entries", &self.entries)
+            .finish()
+    }
+}
+
+/// maps are equal when they have the same entries in the same order
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl IntoIterator for Map {
+    type Item = (String, Value);
+    type IntoIter = std::vec::IntoIter<(String, Value)>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl FromIterator<(String, Value)> for Map {
+    fn from_iter<T: IntoIterator<Item = (String, Value)>>(iter: T) -> Self {
+        let mut map = Map::new();
+        for (k, v) in iter {
+            map.insert(k, v);
+        }
+        map
+    }
+}
+
+/// A type which can be used to look into a `Value`: a `usize`
+/// for an array, a string for a map
+pub trait ValueIndex: private::Sealed {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value>;
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value>;
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for str {}
+    impl Sealed for String {}
+    impl<T: ?Sized + Sealed> Sealed for &T {}
+}
+
+impl ValueIndex for usize {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        v.as_array().and_then(|arr| arr.get(*self))
+    }
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        v.as_array_mut().and_then(|arr| arr.get_mut(*self))
+    }
+}
+impl ValueIndex for str {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        v.as_map().and_then(|map| map.get(self))
+    }
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        v.as_map_mut().and_then(|map| map.get_mut(self))
+    }
+}
+impl ValueIndex for String {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        self.as_str().index_into(v)
+    }
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        self.as_str().index_into_mut(v)
+    }
+}
+impl<T: ?Sized + ValueIndex> ValueIndex for &T {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        (**self).index_into(v)
+    }
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).index_into_mut(v)
+    }
+}
+
+/// Index into a map or an array, returning `Value::Null`
+/// when there's nothing at this index
+impl<I: ValueIndex> ops::Index<I> for Value {
+    type Output = Value;
+    fn index(&self, index: I) -> &Value {
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(v: bool) -> Self {
+        Self::Bool(v)
+    }
+}
+impl From<Number> for Value {
+    fn from(v: Number) -> Self {
+        Self::Number(v)
+    }
+}
+impl From<u64> for Value {
+    fn from(v: u64) -> Self {
+        Self::Number(v.into())
+    }
+}
+impl From<i64> for Value {
+    fn from(v: i64) -> Self {
+        Self::Number(v.into())
+    }
+}
+impl From<u128> for Value {
+    fn from(v: u128) -> Self {
+        Self::Number(v.into())
+    }
+}
+impl From<i128> for Value {
+    fn from(v: i128) -> Self {
+        Self::Number(v.into())
+    }
+}
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Self::Number(v.into())
+    }
+}
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Self::String(v.to_string())
+    }
+}
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Self::String(v)
+    }
+}
+impl From<Vec<Value>> for Value {
+    fn from(v: Vec<Value>) -> Self {
+        Self::Array(v)
+    }
+}
+impl From<Map> for Value {
+    fn from(v: Map) -> Self {
+        Self::Map(v)
+    }
+}
@@ -0,0 +1,314 @@
+//! Deserialization of Hjson into a `Value`, and of
+//! a `Value` into any deserializable type
+//!
+use {
+    crate::{
+        error::{Error, Result},
+        value::*,
+    },
+    serde::{
+        de::{
+            self,
+            value::{MapDeserializer, SeqDeserializer},
+            DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, Unexpected,
+            VariantAccess, Visitor,
+        },
+        forward_to_deserialize_any, Deserialize,
+    },
+    std::fmt,
+};
+
+#[cfg(feature = "arbitrary_precision")]
+use crate::de_number::{RawNumber, NUMBER_TOKEN};
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any Hjson value")
+    }
+    fn visit_bool<E>(self, v: bool) -> std::result::Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+    fn visit_i64<E>(self, v: i64) -> std::result::Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+    fn visit_u64<E>(self, v: u64) -> std::result::Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+    fn visit_f64<E>(self, v: f64) -> std::result::Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+    fn visit_i128<E>(self, v: i128) -> std::result::Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+    fn visit_u128<E>(self, v: u128) -> std::result::Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+    fn visit_str<E>(self, v: &str) -> std::result::Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+    fn visit_string<E>(self, v: String) -> std::result::Result<Value, E> {
+        Ok(Value::String(v))
+    }
+    fn visit_none<E>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+    fn visit_unit<E>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+    fn visit_some<D>(self, deserializer: D) -> std::result::Result<Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        Deserialize::deserialize(deserializer)
+    }
+    fn visit_newtype_struct<D>(self, deserializer: D) -> std::result::Result<Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        Deserialize::deserialize(deserializer)
+    }
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut arr = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            arr.push(value);
+        }
+        Ok(Value::Array(arr))
+    }
+    fn visit_map<A>(self, mut access: A) -> std::result::Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut map = Map::new();
+        while let Some(key) = access.next_key::<String>()? {
+            #[cfg(feature = "arbitrary_precision")]
+            if map.is_empty() && key == NUMBER_TOKEN {
+                return Ok(Value::Number(Number::from_raw(access.next_value()?)));
+            }
+            map.insert(key, access.next_value()?);
+        }
+        Ok(Value::Map(map))
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Map {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        match deserializer.deserialize_map(ValueVisitor)? {
+            Value::Map(map) => Ok(map),
+            // a deserializer may visit another type than a map
+            value => Err(de::Error::invalid_type(value.unexpected(), &"a map")),
+        }
+    }
+}
+
+impl Value {
+    /// describe the value in an error about an unexpected type
+    fn unexpected(&self) -> Unexpected<'_> {
+        match self {
+            Value::Null => Unexpected::Unit,
+            Value::Bool(b) => Unexpected::Bool(*b),
+            Value::Number(n) => {
+                if let Some(v) = n.as_u64() {
+                    Unexpected::Unsigned(v)
+                } else if let Some(v) = n.as_i64() {
+                    Unexpected::Signed(v)
+                } else if n.is_f64() {
+                    Unexpected::Float(n.as_f64())
+                } else {
+                    Unexpected::Other("integer")
+                }
+            }
+            Value::String(s) => Unexpected::Str(s),
+            Value::Array(_) => Unexpected::Seq,
+            Value::Map(_) => Unexpected::Map,
+        }
+    }
+}
+
+impl Number {
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if let Some(v) = self.as_u64() {
+            visitor.visit_u64(v)
+        } else if let Some(v) = self.as_i64() {
+            visitor.visit_i64(v)
+        } else if let Some(v) = self.as_u128() {
+            visitor.visit_u128(v)
+        } else if let Some(v) = self.as_i128() {
+            visitor.visit_i128(v)
+        } else {
+            #[cfg(feature = "arbitrary_precision")]
+            if let Some(s) = self.as_raw_str() {
+                return visitor.visit_map(RawNumber::new(s));
+            }
+            visitor.visit_f64(self.as_f64())
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Self;
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// A `Value` can be deserialized into any compatible type
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::Number(n) => n.visit(visitor),
+            Value::String(v) => visitor.visit_string(v),
+            Value::Array(arr) => {
+                let mut seq = SeqDeserializer::new(arr.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Value::Map(map) => {
+                let mut map = MapDeserializer::new(map.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    /// numbers are given as floats, even when `deserialize_any`
+    /// would give their exact text (`arbitrary_precision` feature)
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Number(n) => visitor.visit_f64(n.as_f64()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+            Value::Map(map) if map.len() == 1 => {
+                let (variant, value) = map.into_iter().next().unwrap();
+                visitor.visit_enum(ValueEnum { variant, value })
+            }
+            _ => Err(de::Error::custom("expected an enum: a string or a map with a single key")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// the enum access for a variant with data,
+/// i.e. a map with a single key
+struct ValueEnum {
+    variant: String,
+    value: Value,
+}
+
+impl<'de> EnumAccess<'de> for ValueEnum {
+    type Error = Error;
+    type Variant = Value;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Value)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant: de::value::StringDeserializer<Error> = self.variant.into_deserializer();
+        let v = seed.deserialize(variant)?;
+        Ok((v, self.value))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Value {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        match self {
+            Value::Null => Ok(()),
+            _ => Err(de::Error::custom("expected a unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
@@ -0,0 +1,65 @@
+use {
+    crate::value::*,
+    serde::ser::{Serialize, SerializeMap, Serializer},
+};
+
+#[cfg(feature = "arbitrary_precision")]
+use {
+    crate::de_number::NUMBER_TOKEN,
+    serde::ser::SerializeStruct,
+};
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(v) => serializer.serialize_bool(*v),
+            Value::Number(n) => n.serialize(serializer),
+            Value::String(v) => serializer.serialize_str(v),
+            Value::Array(arr) => arr.serialize(serializer),
+            Value::Map(map) => map.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for Number {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[cfg(feature = "arbitrary_precision")]
+        if let Some(s) = self.as_raw_str() {
+            // the serializers knowing the token write the text as is
+            let mut number = serializer.serialize_struct(NUMBER_TOKEN, 1)?;
+            number.serialize_field(NUMBER_TOKEN, s)?;
+            return number.end();
+        }
+        if let Some(v) = self.as_u64() {
+            serializer.serialize_u64(v)
+        } else if let Some(v) = self.as_i64() {
+            serializer.serialize_i64(v)
+        } else if let Some(v) = self.as_u128() {
+            serializer.serialize_u128(v)
+        } else if let Some(v) = self.as_i128() {
+            serializer.serialize_i128(v)
+        } else {
+            serializer.serialize_f64(self.as_f64())
+        }
+    }
+}
+
+impl Serialize for Map {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (k, v) in self.iter() {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
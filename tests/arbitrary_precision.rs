@@ -0,0 +1,100 @@
+#![cfg(feature = "arbitrary_precision")]
+
+use {
+    deser_hjson::*,
+    serde::{
+        de::{self, MapAccess, Visitor},
+        Deserialize,
+    },
+    std::fmt,
+};
+
+#[macro_use] mod common;
+
+/// a decimal type accepting numbers given with their text,
+/// as serde_json's arbitrary precision does
+#[derive(Debug, PartialEq)]
+struct Decimal(String);
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct DecimalVisitor;
+        impl<'de> Visitor<'de> for DecimalVisitor {
+            type Value = Decimal;
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a decimal")
+            }
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Decimal, E> {
+                Ok(Decimal(v.to_string()))
+            }
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Decimal, E> {
+                Ok(Decimal(v.to_string()))
+            }
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Decimal, A::Error> {
+                match map.next_key::<String>()? {
+                    Some(key) if key == "$serde_json::private::Number" => {
+                        Ok(Decimal(map.next_value()?))
+                    }
+                    _ => Err(de::Error::custom("not a number")),
+                }
+            }
+        }
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Limits {
+    daily: Decimal,
+    ratio: f64,
+    count: Decimal,
+}
+
+static HJSON: &str = r#"
+daily: 0.1000000000000000055511151231257827
+ratio: 0.5
+count: -12
+"#;
+
+#[test]
+fn test_decimal() {
+    let limits: Limits = from_str(HJSON).unwrap();
+    assert_eq!(limits, Limits {
+        daily: Decimal("0.1000000000000000055511151231257827".to_string()),
+        ratio: 0.5,
+        count: Decimal("-12".to_string()),
+    });
+    // through a Value, the text is kept too
+    let value: Value = from_str(HJSON).unwrap();
+    let limits_from_value: Limits = from_value(value).unwrap();
+    assert_eq!(limits_from_value, limits);
+}
+
+#[test]
+fn test_value_keeps_the_text() {
+    let value: Value = from_str(r#"[
+        1.50
+        -0
+        6.02E+23
+        1e400
+        123456789012345678901234567890123456789012
+        42
+    ]"#).unwrap();
+    let n = value[0].as_number().unwrap();
+    assert!(n.is_f64());
+    assert_eq!(n.as_raw_str(), Some("1.50"));
+    assert_eq!(n.as_f64(), 1.5);
+    assert_eq!(n.to_string(), "1.50");
+    assert_eq!(value[1].as_i64(), Some(0));
+    assert_eq!(value[5].as_u64(), Some(42));
+    assert_eq!(value[5].as_number().unwrap().as_raw_str(), None);
+    assert_eq!(
+        to_string(&value).unwrap(),
+        "[1.50,0,6.02E+23,1e400,123456789012345678901234567890123456789012,42]",
+    );
+    let value: Value = from_str("a: 18446744073709551616").unwrap();
+    assert_eq!(from_value::<u128>(value["a"].clone()).unwrap(), 18446744073709551616);
+}
@@ -0,0 +1,106 @@
+use {
+    deser_hjson::*,
+    serde::Deserialize,
+};
+
+#[macro_use] mod common;
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Ids {
+    hash: u128,
+    offset: i128,
+    small: u128,
+}
+
+#[test]
+fn test_typed_128_bits_integers() {
+    let hjson = r#"
+        hash: 340282366920938463463374607431768211455
+        offset: -170141183460469231731687303715884105728
+        small: 12
+    "#;
+    let ids: Ids = from_str(hjson).unwrap();
+    assert_eq!(ids, Ids {
+        hash: u128::MAX,
+        offset: i128::MIN,
+        small: 12,
+    });
+    assert_eq!(from_str::<u128>("18446744073709551616").unwrap(), u64::MAX as u128 + 1);
+    assert_eq!(from_str::<i128>("-9223372036854775809").unwrap(), i64::MIN as i128 - 1);
+    assert!(matches!(
+        from_str::<u128>("340282366920938463463374607431768211456"),
+        Err(Error::Syntax { code: ErrorCode::ExpectedU128, .. }),
+    ));
+    assert!(matches!(
+        from_str::<u128>("-1"),
+        Err(Error::Syntax { code: ErrorCode::ExpectedPositiveInteger, .. }),
+    ));
+    assert!(matches!(
+        from_str::<i128>("-170141183460469231731687303715884105729"),
+        Err(Error::Syntax { code: ErrorCode::ExpectedI128, .. }),
+    ));
+}
+
+/// check integers which don't fit in 64 bits are visited as 128 bits
+/// integers by `deserialize_any`, before falling back to floats
+#[test]
+fn test_untyped_big_integers() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Id {
+        #[serde(deserialize_with = "any_u128")]
+        id: u128,
+    }
+    fn any_u128<'de, D: serde::Deserializer<'de>>(d: D) -> std::result::Result<u128, D::Error> {
+        struct V;
+        impl<'de> serde::de::Visitor<'de> for V {
+            type Value = u128;
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a 128 bits integer")
+            }
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<u128, E> {
+                Ok(v as u128)
+            }
+            fn visit_u128<E>(self, v: u128) -> std::result::Result<u128, E> {
+                Ok(v)
+            }
+        }
+        serde::Deserializer::deserialize_any(d, V)
+    }
+    let id: Id = from_str("id: 98765432109876543210").unwrap();
+    assert_eq!(id.id, 98765432109876543210);
+    let id: Id = from_str("id: 42").unwrap();
+    assert_eq!(id.id, 42);
+    let value: Value = from_str("[18446744073709551615, 18446744073709551616, -9223372036854775809]").unwrap();
+    assert_eq!(value[0].as_u64(), Some(u64::MAX));
+    assert_eq!(value[1].as_u64(), None);
+    assert_eq!(value[1].as_u128(), Some(u64::MAX as u128 + 1));
+    assert_eq!(value[2].as_i128(), Some(i64::MIN as i128 - 1));
+    // integers not fitting in 128 bits are floats
+    let value: Value = from_str("a: 1000000000000000000000000000000000000000").unwrap();
+    assert!(value["a"].as_number().unwrap().is_f64());
+    assert_eq!(value["a"].as_f64(), Some(1e39));
+}
+
+/// check 128 bits integers are kept exact in a `Value`
+#[test]
+fn test_value_128_bits_integers() {
+    let hjson = "[340282366920938463463374607431768211455, -170141183460469231731687303715884105728]";
+    let value: Value = from_str(hjson).unwrap();
+    let max = value[0].as_number().unwrap();
+    assert_eq!(max.as_u128(), Some(u128::MAX));
+    assert_eq!(max.as_i128(), None);
+    assert!(!max.is_f64());
+    assert_eq!(max.to_string(), "340282366920938463463374607431768211455");
+    let min = value[1].as_number().unwrap();
+    assert_eq!(min.as_i128(), Some(i128::MIN));
+    assert_eq!(min.as_u128(), None);
+    assert_eq!(from_value::<u128>(value[0].clone()).unwrap(), u128::MAX);
+    assert_eq!(from_value::<i128>(value[1].clone()).unwrap(), i128::MIN);
+    assert_eq!(to_string(&value).unwrap(), hjson.replace(", ", ","));
+    let read: Value = from_str(&to_string(&value).unwrap()).unwrap();
+    assert_eq!(read, value);
+    assert_eq!(Value::from(u128::MAX), value[0]);
+    // and they're normalized, so that small ones are the same as 64 bits ones
+    assert_eq!(Value::from(12u128), Value::from(12u64));
+    assert_eq!(Value::from(-12i128), Value::from(-12i64));
+}
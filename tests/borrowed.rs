@@ -0,0 +1,75 @@
+use {
+    deser_hjson::*,
+    serde::Deserialize,
+    std::{
+        borrow::Cow,
+        collections::HashMap,
+    },
+};
+
+#[macro_use] mod common;
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Borrowing<'a> {
+    quoteless: &'a str,
+    quoted: &'a str,
+    single_quoted: &'a str,
+    #[serde(borrow)]
+    map: HashMap<&'a str, &'a str>,
+    #[serde(borrow)]
+    escaped: Cow<'a, str>,
+    #[serde(borrow)]
+    multiline: Cow<'a, str>,
+    #[serde(borrow)]
+    list: Vec<&'a str>,
+    #[serde(borrow)]
+    opt: Option<&'a str>,
+}
+
+#[test]
+fn test_borrowed_strs() {
+    let hjson = r#"
+    {
+        quoteless: a quoteless string
+        quoted: "a quoted string"
+        single_quoted: 'a single quoted string'
+        map: {
+            key: value
+            "quoted key": 'quoted value'
+        }
+        escaped: "tab\there"
+        multiline:
+            '''
+            two
+            lines
+            '''
+        list: [
+            one
+            "two"
+        ]
+        opt: "some"
+    }
+    "#;
+    let b: Borrowing = from_str_borrowed(hjson).unwrap();
+    assert_eq!(b.quoteless, "a quoteless string");
+    assert_eq!(b.quoted, "a quoted string");
+    assert_eq!(b.single_quoted, "a single quoted string");
+    assert_eq!(b.map.get("key"), Some(&"value"));
+    assert_eq!(b.map.get("quoted key"), Some(&"quoted value"));
+    assert_eq!(b.escaped, Cow::<str>::Owned("tab\there".to_string()));
+    assert!(matches!(b.escaped, Cow::Owned(_)));
+    assert_eq!(b.multiline, "two\nlines");
+    assert_eq!(b.list, vec!["one", "two"]);
+    assert_eq!(b.opt, Some("some"));
+}
+
+#[test]
+fn test_escape_cant_be_borrowed() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct S<'a> {
+        s: &'a str,
+    }
+    assert!(from_str_borrowed::<S>(r#"s: "a\nb""#).is_err());
+    assert!(from_str_borrowed::<S>(r#"s: "a b""#).is_ok());
+}
@@ -39,3 +39,44 @@ fn test_braceless() {
     );
 }
 
+
+/// check that enums buffering their content read the braceless root
+/// as a map, and not recursively
+#[test]
+fn test_braceless_buffered_enums() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(tag = "type")]
+    enum Tagged {
+        A { x: u32 },
+        B,
+    }
+    assert_eq!(
+        deser_hjson::from_str::<Tagged>("type: A\nx: 3\n").unwrap(),
+        Tagged::A { x: 3 },
+    );
+    assert_eq!(
+        deser_hjson::from_str::<Tagged>("type: B").unwrap(),
+        Tagged::B,
+    );
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Untagged {
+        S(String),
+        N(u32),
+    }
+    assert!(deser_hjson::from_str::<Untagged>("a: b").is_err());
+    assert_eq!(
+        deser_hjson::from_str::<Untagged>("a b").unwrap(),
+        Untagged::S("a b".to_string()),
+    );
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum WithMap {
+        N(u32),
+        M { a: String },
+    }
+    assert_eq!(
+        deser_hjson::from_str::<WithMap>("a: b").unwrap(),
+        WithMap::M { a: "b".to_string() },
+    );
+}
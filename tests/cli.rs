@@ -0,0 +1,117 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+static HJSON: &str = r#"
+// the servers
+servers: [
+    {
+        host: example.com
+        port: 80
+    }
+]
+"#;
+
+/// run the hjson binary with the given arguments and input
+fn hjson(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hjson"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+fn stderr(output: &Output) -> &str {
+    std::str::from_utf8(&output.stderr).unwrap()
+}
+
+#[test]
+fn test_check() {
+    let output = hjson(&["check"], HJSON);
+    assert!(output.status.success());
+    let output = hjson(&["check", "-"], "{\n  a: 1\n  b 2\n}");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("error: b: expected a colon after the map key"));
+    assert!(stderr(&output).contains(" --> <stdin>:3:5"));
+    let output = hjson(&["check", "--json"], HJSON);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("comments are disallowed"));
+    let output = hjson(&["check", "--json"], r#"{"a": [1, 2]}"#);
+    assert!(output.status.success());
+    let output = hjson(&["check", "tests/no-such-file.hjson"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("error: can't read tests/no-such-file.hjson"));
+}
+
+#[test]
+fn test_json_conversions() {
+    let output = hjson(&["to-json"], HJSON);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "{\"servers\":[{\"host\":\"example.com\",\"port\":80}]}\n");
+    let output = hjson(&["to-json", "--pretty"], HJSON);
+    assert!(stdout(&output).starts_with("{\n  \"servers\": [\n"));
+    let output = hjson(&["from-json"], r#"{"a": [1, "b c"]}"#);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "{\n  a: [\n    1\n    b c\n  ]\n}\n");
+    let output = hjson(&["from-json"], "{a: 1}");
+    assert_eq!(output.status.code(), Some(1));
+    let output = hjson(&["to-json"], "a: [");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_fmt() {
+    let output = hjson(&["fmt"], HJSON);
+    assert!(output.status.success());
+    let formatted = stdout(&output);
+    assert_eq!(
+        formatted,
+        "{\n  servers: [\n    {\n      host: example.com\n      port: 80\n    }\n  ]\n}\n",
+    );
+    assert!(hjson(&["fmt", "--check"], formatted).status.success());
+    let output = hjson(&["fmt", "--check"], HJSON);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "<stdin> isn't formatted\n");
+}
+
+#[test]
+fn test_get() {
+    let output = hjson(&["get", "servers[0].port"], HJSON);
+    assert_eq!(stdout(&output), "80\n");
+    let output = hjson(&["get", "servers[0].host", "-"], HJSON);
+    assert_eq!(stdout(&output), "example.com\n");
+    let output = hjson(&["get", "servers[0]"], HJSON);
+    assert_eq!(stdout(&output), "{\n  host: example.com\n  port: 80\n}\n");
+    let output = hjson(&["get", "servers[1]"], HJSON);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "error: no value at servers[1]\n");
+}
+
+#[test]
+fn test_wrong_commands() {
+    for args in [
+        &[][..],
+        &["convert"],
+        &["get"],
+        &["to-json", "--ugly"],
+        &["fmt", "a.hjson", "b.hjson"],
+    ] {
+        let output = hjson(args, "");
+        assert_eq!(output.status.code(), Some(2), "args: {:?}", args);
+        assert!(stderr(&output).contains("Usage: hjson"));
+    }
+    let output = hjson(&["help"], "");
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Usage: hjson"));
+}
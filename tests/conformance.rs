@@ -0,0 +1,140 @@
+//! Check the deserializer against the cases of the Hjson test
+//! suite, found in the hjson-test-suite directory:
+//! - `name_test.hjson` (or `.json`) files with a `name_result.json`
+//!   file must be read as the same tree than the JSON one
+//! - `failName_test.hjson` (or `.json`) files must be rejected
+//!
+//! The cases are run both with `from_str` and with `transcode`.
+use {
+    serde_json::Value as Json,
+    std::{
+        fs,
+        path::Path,
+    },
+};
+
+/// the cases on which we knowingly deviate from the spec, with the reason
+static SKIPPED: &[(&str, &str)] = &[
+    (
+        "quotelessStar",
+        "a '*' before a value is skipped like a comment char, so `*.rs` is read as `.rs`",
+    ),
+];
+
+/// tell whether the trees are the same, numbers being compared
+/// by value (`1701` must match `17.01e2`)
+fn same(a: &Json, b: &Json) -> bool {
+    match (a, b) {
+        (Json::Number(a), Json::Number(b)) => a.as_f64() == b.as_f64(),
+        (Json::Array(a), Json::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same(a, b))
+        }
+        (Json::Object(a), Json::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| same(v, w)))
+        }
+        _ => a == b,
+    }
+}
+
+/// a way to read a Hjson text into a JSON tree
+type Reader = fn(&str) -> Result<Json, String>;
+
+#[cfg(not(feature = "arbitrary_precision"))]
+fn deserialize(input: &str) -> Result<Json, String> {
+    deser_hjson::from_str::<Json>(input).map_err(|e| e.to_string())
+}
+
+// without its own arbitrary_precision feature, serde_json can't read
+// the numbers given with their text, so the text is read into a `Value`
+#[cfg(feature = "arbitrary_precision")]
+fn deserialize(input: &str) -> Result<Json, String> {
+    fn to_json(value: &deser_hjson::Value) -> Json {
+        use deser_hjson::Value;
+        match value {
+            Value::Null => Json::Null,
+            Value::Bool(b) => Json::Bool(*b),
+            Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+                (Some(v), _) => v.into(),
+                (_, Some(v)) => v.into(),
+                _ => n.as_f64().into(),
+            },
+            Value::String(s) => Json::String(s.clone()),
+            Value::Array(items) => Json::Array(items.iter().map(to_json).collect()),
+            Value::Map(map) => Json::Object(
+                map.iter().map(|(k, v)| (k.clone(), to_json(v))).collect()
+            ),
+        }
+    }
+    deser_hjson::from_str(input)
+        .map(|value| to_json(&value))
+        .map_err(|e| e.to_string())
+}
+
+fn transcode(input: &str) -> Result<Json, String> {
+    let mut json = Vec::new();
+    deser_hjson::transcode(input, &mut json).map_err(|e| e.to_string())?;
+    serde_json::from_slice(&json).map_err(|e| format!("invalid JSON output: {}", e))
+}
+
+/// run a case, returning a description of the problem if it fails
+fn check(dir: &Path, name: &str, input: &str, reader: Reader) -> Result<(), String> {
+    let read = reader(input);
+    if name.starts_with("fail") {
+        return match read {
+            Ok(tree) => Err(format!("should have been rejected but was read as {}", tree)),
+            Err(_) => Ok(()),
+        };
+    }
+    let expected = fs::read_to_string(dir.join(format!("{}_result.json", name)))
+        .map_err(|e| format!("no result file: {}", e))?;
+    let expected: Json = serde_json::from_str(&expected).expect("valid JSON in result file");
+    match read {
+        Ok(tree) if same(&tree, &expected) => Ok(()),
+        Ok(tree) => Err(format!("read as {}\n    instead of {}", tree, expected)),
+        Err(e) => Err(format!("failed with {}", e)),
+    }
+}
+
+fn check_suite(reader: Reader) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/hjson-test-suite");
+    let mut cases: Vec<(String, String)> = fs::read_dir(&dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let file_name = entry.unwrap().file_name().into_string().unwrap();
+            let name = file_name
+                .strip_suffix("_test.hjson")
+                .or_else(|| file_name.strip_suffix("_test.json"))?
+                .to_string();
+            Some((name, file_name))
+        })
+        .collect();
+    cases.sort();
+    assert!(!cases.is_empty());
+    let mut problems = Vec::new();
+    for (name, file_name) in &cases {
+        let input = fs::read_to_string(dir.join(file_name)).unwrap();
+        let result = check(&dir, name, &input, reader);
+        let skipped = SKIPPED.iter().any(|&(skipped, _)| skipped == name);
+        match (result, skipped) {
+            (Err(problem), false) => problems.push(format!("{}: {}", name, problem)),
+            (Ok(()), true) => problems.push(format!("{}: passes, remove it from SKIPPED", name)),
+            _ => {}
+        }
+    }
+    for &(skipped, _) in SKIPPED {
+        if !cases.iter().any(|(name, _)| name == skipped) {
+            problems.push(format!("{}: skipped but not found", skipped));
+        }
+    }
+    assert!(problems.is_empty(), "\n{}\n", problems.join("\n"));
+}
+
+#[test]
+fn test_hjson_suite() {
+    check_suite(deserialize);
+}
+
+#[test]
+fn test_hjson_suite_transcoded() {
+    check_suite(transcode);
+}
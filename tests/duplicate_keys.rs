@@ -0,0 +1,157 @@
+use {
+    deser_hjson::*,
+    serde::Deserialize,
+    std::collections::HashMap,
+};
+
+#[macro_use] mod common;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+static HJSON: &str = r#"
+{
+    host: localhost
+    port: 8080
+    "host": example.com
+    tags: {
+        a: 1
+        host: 2
+    }
+    port: 80
+}
+"#;
+
+fn options(policy: DuplicateKeys) -> DeserializerOptions {
+    DeserializerOptions::new().duplicate_keys(policy)
+}
+
+#[test]
+fn test_last_wins() {
+    let map: HashMap<String, Value> = from_str(HJSON).unwrap();
+    assert_eq!(map["host"], Value::String("example.com".to_owned()));
+    assert_eq!(map["port"], Value::from(80u64));
+    // a derived struct rejects the duplicate field
+    assert!(from_str::<Server>(HJSON).is_err());
+}
+
+#[test]
+fn test_first_wins() {
+    let options = options(DuplicateKeys::FirstWins);
+    let map: HashMap<String, Value> = from_str_with_options(HJSON, &options).unwrap();
+    assert_eq!(map["host"], Value::String("localhost".to_owned()));
+    assert_eq!(map["port"], Value::from(8080u64));
+    assert_eq!(map.len(), 3);
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        host: String,
+        port: u16,
+        tags: HashMap<String, u8>,
+    }
+    let config: Config = from_str_with_options(HJSON, &options).unwrap();
+    assert_eq!(config.host, "localhost");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.tags.len(), 2);
+    // the skipped values are still checked
+    let hjson = "a: 1\na: [2, 3\nb: 4";
+    assert!(from_str_with_options::<Value>(hjson, &options).is_err());
+}
+
+#[test]
+fn test_error() {
+    let options = options(DuplicateKeys::Error);
+    let err = from_str_with_options::<Server>(HJSON, &options).unwrap_err();
+    match err {
+        Error::Syntax { code: ErrorCode::DuplicateKey { key, line, col }, .. } => {
+            assert_eq!(key, "host");
+            assert_eq!((line, col), (3, 5));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    let err = from_str_with_options::<Value>(HJSON, &options).unwrap_err();
+    assert_eq!(err.location(), Some((5, 5)));
+    assert_eq!(
+        err.render(HJSON, None).lines().next(),
+        Some(r#"error: duplicate key "host", first found at 3:5"#),
+    );
+    // the same key in different maps isn't a duplicate
+    let hjson = "a: {b: 1}\nc: {b: 2}\nd: [{b: 3}, {b: 4}]";
+    assert!(from_str_with_options::<Value>(hjson, &options).is_ok());
+    // the key checking stops at the root delimiter
+    let hjson = "a: 1\n---\na: 2";
+    let options = options.root_delimiter("---");
+    let (v, end) = from_str_prefix_with_options::<Value>(hjson, &options).unwrap();
+    assert_eq!(v["a"], Value::from(1u64));
+    assert_eq!(end, 9);
+}
+
+#[test]
+fn test_error_in_nested_map() {
+    let options = options(DuplicateKeys::Error);
+    let hjson = "servers: [\n  {\n    port: 80\n    port: 81\n  }\n]";
+    let err = from_str_with_options::<Value>(hjson, &options).unwrap_err();
+    assert_eq!(err.path().unwrap().to_string(), "servers[0]");
+    match err {
+        Error::Syntax { code: ErrorCode::DuplicateKey { key, line, col }, line: l, col: c, .. } => {
+            assert_eq!(key, "port");
+            assert_eq!((line, col), (3, 5));
+            assert_eq!((l, c), (4, 5));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn test_error_from_reader() {
+    let options = options(DuplicateKeys::Error);
+    let mut hjson = String::new();
+    for i in 0..10_000 {
+        hjson.push_str(&format!("key_{}: value {}\n", i, i));
+    }
+    hjson.push_str("key_3: again\n");
+    let mut deserializer = Deserializer::reader_with_options(hjson.as_bytes(), options);
+    let err = Value::deserialize(&mut deserializer).unwrap_err();
+    match err {
+        Error::Syntax { code: ErrorCode::DuplicateKey { key, line, col }, line: l, .. } => {
+            assert_eq!(key, "key_3");
+            assert_eq!((line, col), (4, 1));
+            assert_eq!(l, 10_001);
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn test_warn() {
+    let mut deserializer = Deserializer::with_options(HJSON, options(DuplicateKeys::Warn));
+    let map = HashMap::<String, Value>::deserialize(&mut deserializer).unwrap();
+    assert_eq!(map["port"], Value::from(80u64));
+    let warnings: Vec<(usize, usize)> = deserializer.warnings()
+        .iter()
+        .filter_map(|warning| warning.location())
+        .collect();
+    assert_eq!(warnings, vec![(5, 5), (10, 5)]);
+}
+
+/// keys are compared once unescaped, but as strings
+#[test]
+fn test_escaped_keys() {
+    let options = options(DuplicateKeys::Error);
+    for hjson in [
+        r#"{"a": 1, "\u0061": 2}"#,
+        r#"{a: 1, 'a': 2}"#,
+        r#"{"é": 1, "\u00e9": 2}"#,
+        r#"{"a\"b": 1, 'a"b': 2}"#,
+    ] {
+        let err = from_str_with_options::<Value>(hjson, &options).unwrap_err();
+        assert!(
+            matches!(err, Error::Syntax { code: ErrorCode::DuplicateKey { line: 1, col: 2, .. }, .. }),
+            "{:?} in {}", err, hjson,
+        );
+    }
+    let map: HashMap<u8, u8> = from_str_with_options("{1: 1, 01: 2}", &options).unwrap();
+    assert_eq!(map[&1], 2);
+}
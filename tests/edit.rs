@@ -0,0 +1,236 @@
+use {
+    deser_hjson::{
+        *,
+        edit::{Document, EditError},
+    },
+};
+
+#[macro_use] mod common;
+
+static HJSON: &str = r#"
+# the version, to be bumped
+version: "1.2.3"
+/* the servers
+   we deploy to */
+servers: [
+  {
+    name: alpha // the main one
+    'port': 8080
+  }
+  { name: "beta", port: 8081 }
+]
+description:
+  '''
+  A multiline
+  text
+  '''
+"#;
+
+fn path(s: &str) -> Path {
+    s.parse().unwrap()
+}
+
+fn doc(s: &str) -> Document {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_lossless_round_trip() {
+    for hjson in &[
+        HJSON,
+        "",
+        "\n// just a comment",
+        "a: 1",
+        "{a:1,b:2}",
+        "  {  a : 1 , 'b' : [ 1 , 2 ] , } # end\n",
+        "[\n  1\n  2,\n  # comment\n]",
+        "\"just a string\"",
+        "{\n  a: 1\n  b: {c: \"x\\ny\"}\n}",
+        "\r\na: 1\r\nb: text\r\n",
+        // a root scalar keeps what follows it
+        "hello\n",
+        "hello  \n\n",
+        "true \r\n",
+        "3 // three\n",
+        "/* before */ 'quoted' \n",
+    ] {
+        assert_eq!(doc(hjson).to_string(), *hjson);
+    }
+}
+
+#[test]
+fn test_same_values_as_deserialization() {
+    let value: Value = from_str(HJSON).unwrap();
+    assert_eq!(doc(HJSON).get(&Path::new()), Some(value));
+    assert_eq!(doc(HJSON).get(&path("servers[1].name")), Some(Value::from("beta")));
+    assert_eq!(doc(HJSON).get(&path("servers[0].port")), Some(Value::from(8080u64)));
+    assert_eq!(doc(HJSON).get(&path("servers[2]")), None);
+    assert_eq!(doc(HJSON).get(&path("version.major")), None);
+}
+
+#[test]
+fn test_invalid_document() {
+    let err = Document::parse("a: 1\nb: [1, 2").unwrap_err();
+    assert!(err.is_eof());
+    assert!(Document::parse("[1, 2] 3").is_err());
+    // unclosed block comments
+    for hjson in ["/*a", "/*", "a: 1\n/* b", "[1] /*", "{a: /* 1}", "[1, /* 2]"] {
+        assert!(Document::parse(hjson).is_err(), "{:?} should be rejected", hjson);
+    }
+}
+
+#[test]
+fn test_set() {
+    let mut doc = doc(HJSON);
+    let old = doc.set(&path("version"), Value::from("1.2.4")).unwrap();
+    assert_eq!(old, Value::from("1.2.3"));
+    doc.set(&path("servers[0].port"), Value::from(9000u64)).unwrap();
+    doc.set(&path("servers[0].name"), Value::from("gamma")).unwrap();
+    doc.set(&path("servers[1].name"), Value::from("delta")).unwrap();
+    assert_eq!(
+        doc.set(&path("servers[2].name"), Value::from("x")),
+        Err(EditError::NotFound(path("servers[2]"))),
+    );
+    assert_eq!(
+        doc.set(&path("version.major"), Value::from(1u64)),
+        Err(EditError::NotAContainer(path("version"))),
+    );
+    assert_eq!(doc.to_string(), r#"
+# the version, to be bumped
+version: "1.2.4"
+/* the servers
+   we deploy to */
+servers: [
+  {
+    name: gamma
+    'port': 9000
+  }
+  { name: "delta", port: 8081 }
+]
+description:
+  '''
+  A multiline
+  text
+  '''
+"#);
+    let value: Value = from_str(&doc.to_string()).unwrap();
+    assert_eq!(value["servers"][0]["name"], Value::from("gamma"));
+    assert_eq!(value["servers"][1]["name"], Value::from("delta"));
+}
+
+/// the root can be replaced by a multiline string, written
+/// at the very start of the text
+#[test]
+fn test_set_multiline_root() {
+    for (hjson, s) in [(HJSON, "x\ny"), ("a: 1", "first\n  second")] {
+        let mut document = doc(hjson);
+        document.set(&Path::new(), Value::from(s)).unwrap();
+        assert_eq!(from_str::<String>(&document.to_string()).unwrap(), s);
+    }
+}
+
+#[test]
+fn test_set_container() {
+    let mut doc = doc("a: 1\nb: {\n  c: 2\n}\n");
+    let mut map = Map::new();
+    map.insert("x".to_string(), Value::from("some text"));
+    map.insert("y".to_string(), Value::Array(vec![Value::from(1u64), Value::from(2u64)]));
+    doc.set(&path("b.c"), Value::Map(map)).unwrap();
+    assert_eq!(doc.to_string(), "a: 1\nb: {\n  c: {\n    x: some text\n    y: [\n      1\n      2\n    ]\n  }\n}\n");
+    let value: Value = from_str(&doc.to_string()).unwrap();
+    assert_eq!(value["b"]["c"]["x"], Value::from("some text"));
+}
+
+#[test]
+fn test_insert() {
+    let mut doc = doc(HJSON);
+    doc.insert(&path("servers[1].ip"), Value::from("10.0.0.2")).unwrap();
+    doc.insert(&path("servers[0].ip"), Value::from("10.0.0.1")).unwrap();
+    doc.insert(&path("servers[0].name"), Value::from("omega")).unwrap();
+    doc.insert(&path("servers[2]"), Value::Map(Map::new())).unwrap();
+    doc.insert(&path("servers[2].name"), Value::from("epsilon")).unwrap();
+    doc.insert(&path("enabled"), Value::from(true)).unwrap();
+    assert_eq!(
+        doc.insert(&path("servers[5]"), Value::Null),
+        Err(EditError::NotFound(path("servers[5]"))),
+    );
+    assert_eq!(
+        doc.insert(&path("servers.x"), Value::Null),
+        Err(EditError::NotAContainer(path("servers"))),
+    );
+    assert_eq!(
+        doc.insert(&path("missing.x"), Value::Null),
+        Err(EditError::NotFound(path("missing"))),
+    );
+    assert_eq!(doc.to_string(), r#"
+# the version, to be bumped
+version: "1.2.3"
+/* the servers
+   we deploy to */
+servers: [
+  {
+    name: omega
+    'port': 8080
+    ip: "10.0.0.1"
+  }
+  { name: "beta", port: 8081, ip: "10.0.0.2" }
+  {name: "epsilon"}
+]
+description:
+  '''
+  A multiline
+  text
+  '''
+enabled: true
+"#);
+    let value: Value = from_str(&doc.to_string()).unwrap();
+    assert_eq!(value["servers"][0]["ip"], Value::from("10.0.0.1"));
+    assert_eq!(value["servers"][2]["name"], Value::from("epsilon"));
+}
+
+#[test]
+fn test_insert_in_empty_documents() {
+    let mut doc = doc("");
+    doc.insert(&path("a"), Value::from(1u64)).unwrap();
+    doc.insert(&path("b c"), Value::from("x")).unwrap();
+    assert_eq!(doc.to_string(), "a: 1\n\"b c\": x\n");
+    let mut doc = self::doc("// nothing yet");
+    doc.insert(&path("a"), Value::from(1u64)).unwrap();
+    assert_eq!(doc.to_string(), "// nothing yet\na: 1\n");
+    let mut doc = self::doc("[]");
+    doc.insert(&path("[0]"), Value::from(1u64)).unwrap();
+    doc.insert(&path("[0]"), Value::from(0u64)).unwrap();
+    doc.insert(&path("[2]"), Value::from(2u64)).unwrap();
+    assert_eq!(doc.to_string(), "[0, 1, 2]");
+}
+
+#[test]
+fn test_remove() {
+    let mut doc = doc(HJSON);
+    assert_eq!(doc.remove(&path("version")), Some(Value::from("1.2.3")));
+    assert_eq!(doc.remove(&path("servers[0].name")), Some(Value::from("alpha // the main one")));
+    assert_eq!(doc.remove(&path("servers[1].port")), Some(Value::from(8081u64)));
+    assert_eq!(doc.remove(&path("missing")), None);
+    assert_eq!(doc.remove(&path("servers[3]")), None);
+    assert_eq!(doc.remove(&Path::new()), None);
+    assert_eq!(doc.to_string(), r#"
+/* the servers
+   we deploy to */
+servers: [
+  {
+    'port': 8080
+  }
+  { name: "beta" }
+]
+description:
+  '''
+  A multiline
+  text
+  '''
+"#);
+    let mut doc = self::doc("[1, 2, 3]");
+    doc.remove(&path("[0]"));
+    assert_eq!(doc.to_string(), "[2, 3]");
+    doc.remove(&path("[1]"));
+    assert_eq!(doc.to_string(), "[2]");
+}
@@ -133,6 +133,13 @@ fn test_arr_struct_untagged() {
             },
         ],
     };
-    assert_eq!(outer_thing, from_str::<OuterThing>(hjson).unwrap());
+    if cfg!(feature = "arbitrary_precision") {
+        // floats can't be read by untagged enums with arbitrary_precision,
+        // so only the items before the fourth one are read
+        let err = from_str::<OuterThing>(hjson).unwrap_err();
+        assert_eq!(err.path().unwrap().to_string(), "items[3].untagged");
+    } else {
+        assert_eq!(outer_thing, from_str::<OuterThing>(hjson).unwrap());
+    }
 }
 
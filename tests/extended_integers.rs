@@ -0,0 +1,106 @@
+use {
+    deser_hjson::*,
+    serde::Deserialize,
+};
+
+#[macro_use] mod common;
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Registers {
+    mask: u32,
+    mode: u8,
+    flags: u16,
+    offset: i64,
+    count: u64,
+}
+
+static HJSON: &str = r#"
+mask: 0xFF00
+mode: 0o17
+flags: 0b1010_0101
+offset: -0x10
+count: 1_000_000
+"#;
+
+fn options() -> DeserializerOptions {
+    DeserializerOptions::new().extended_integers(true)
+}
+
+fn code<T: std::fmt::Debug>(result: Result<T>) -> ErrorCode {
+    match result {
+        Err(Error::Syntax { code, .. }) => code,
+        r => panic!("unexpected result: {:?}", r),
+    }
+}
+
+#[test]
+fn test_disabled_by_default() {
+    assert!(from_str::<Registers>(HJSON).is_err());
+    let value: Value = from_str(HJSON).unwrap();
+    assert_eq!(value["mask"].as_str(), Some("0xFF00"));
+    assert_eq!(value["count"].as_str(), Some("1_000_000"));
+}
+
+#[test]
+fn test_typed() {
+    let registers: Registers = from_str_with_options(HJSON, &options()).unwrap();
+    assert_eq!(registers, Registers {
+        mask: 0xFF00,
+        mode: 0o17,
+        flags: 0b1010_0101,
+        offset: -0x10,
+        count: 1_000_000,
+    });
+    assert_eq!(from_str_with_options::<i8>("-0x80", &options()).unwrap(), i8::MIN);
+    assert_eq!(from_str_with_options::<u128>("0xFFFF_FFFF_FFFF_FFFF_FFFF", &options()).unwrap(), 0xFFFF_FFFF_FFFF_FFFF_FFFF);
+    // plain integers are still read
+    assert_eq!(from_str_with_options::<u16>("012", &options()).unwrap(), 12);
+}
+
+#[test]
+fn test_overflow_errors() {
+    assert_eq!(code(from_str_with_options::<u8>("0x100", &options())), ErrorCode::ExpectedU8);
+    assert_eq!(code(from_str_with_options::<i8>("-0x81", &options())), ErrorCode::ExpectedI8);
+    assert_eq!(code(from_str_with_options::<u32>("0b1_0000_0000_0000_0000_0000_0000_0000_0000", &options())), ErrorCode::ExpectedU32);
+    assert_eq!(code(from_str_with_options::<i16>("40_000", &options())), ErrorCode::ExpectedI16);
+    assert_eq!(code(from_str_with_options::<u32>("-0x1", &options())), ErrorCode::ExpectedPositiveInteger);
+    assert_eq!(
+        code(from_str_with_options::<Value>("a: 0x1_0000_0000_0000_0000_0000_0000_0000_0000", &options())),
+        ErrorCode::ExpectedU128,
+    );
+}
+
+#[test]
+fn test_untyped() {
+    let value: Value = from_str_with_options(r#"[
+        0xff
+        -0b11
+        1_000
+        0x1F # a comment
+        0x1G
+        1__0
+        _1
+        1_
+        0x_1
+        00_1
+        0x12 bytes
+        0xFFFF_FFFF_FFFF_FFFF_FFFF
+    ]"#, &options()).unwrap();
+    assert_eq!(value[0].as_u64(), Some(255));
+    assert_eq!(value[1].as_i64(), Some(-3));
+    assert_eq!(value[2].as_u64(), Some(1000));
+    assert_eq!(value[3].as_u64(), Some(31));
+    for (idx, s) in ["0x1G", "1__0", "_1", "1_", "0x_1", "00_1", "0x12 bytes"].iter().enumerate() {
+        assert_eq!(value[idx + 4].as_str(), Some(*s));
+    }
+    assert_eq!(value[11].as_u64(), None);
+    assert!(value[11].as_f64().is_some());
+}
+
+#[test]
+fn test_transcode() {
+    let mut deserializer = Deserializer::with_options("a: 0x1F\nb: 1_000\nc: -0b1", options());
+    let mut json = Vec::new();
+    deserializer.transcode(&mut json).unwrap();
+    assert_eq!(String::from_utf8(json).unwrap(), r#"{"a":31,"b":1000,"c":-1}"#);
+}
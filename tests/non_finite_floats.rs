@@ -0,0 +1,99 @@
+use {
+    deser_hjson::*,
+    serde::Deserialize,
+};
+
+#[macro_use] mod common;
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Limits {
+    min: f64,
+    max: f32,
+    step: Option<f64>,
+    threshold: f64,
+}
+
+static HJSON: &str = r#"
+min: -Infinity
+max: Infinity # unbounded
+step: NaN
+threshold: 0.5
+"#;
+
+fn options() -> DeserializerOptions {
+    DeserializerOptions::new().non_finite_floats(true)
+}
+
+#[test]
+fn test_disabled_by_default() {
+    assert!(from_str::<Limits>(HJSON).is_err());
+    let value: Value = from_str(HJSON).unwrap();
+    assert_eq!(value["min"].as_str(), Some("-Infinity"));
+    assert_eq!(value["max"].as_str(), Some("Infinity # unbounded"));
+}
+
+#[test]
+fn test_typed() {
+    let limits: Limits = from_str_with_options(HJSON, &options()).unwrap();
+    assert_eq!(limits.min, f64::NEG_INFINITY);
+    assert_eq!(limits.max, f32::INFINITY);
+    assert!(limits.step.unwrap().is_nan());
+    assert_eq!(limits.threshold, 0.5);
+    let v: Vec<f64> = from_str_with_options("[Infinity, -Infinity, 1]", &options()).unwrap();
+    assert_eq!(v, vec![f64::INFINITY, f64::NEG_INFINITY, 1.0]);
+    // the keywords must be alone on their line
+    assert!(from_str_with_options::<f64>("Infinity and beyond", &options()).is_err());
+    assert!(from_str_with_options::<f64>("infinity", &options()).is_err());
+}
+
+#[test]
+fn test_untyped() {
+    let value: Value = from_str_with_options(r#"[
+        NaN
+        Infinity
+        -Infinity
+        Infinity and beyond
+        NaNa
+        "Infinity"
+    ]"#, &options()).unwrap();
+    assert!(value[0].as_f64().unwrap().is_nan());
+    assert_eq!(value[1].as_f64(), Some(f64::INFINITY));
+    assert_eq!(value[2].as_f64(), Some(f64::NEG_INFINITY));
+    assert_eq!(value[3].as_str(), Some("Infinity and beyond"));
+    assert_eq!(value[4].as_str(), Some("NaNa"));
+    assert_eq!(value[5].as_str(), Some("Infinity"));
+    // map keys aren't affected
+    let value: Value = from_str_with_options("NaN: 1\nInfinity: 2", &options()).unwrap();
+    assert_eq!(value["Infinity"].as_u64(), Some(2));
+}
+
+/// there's no JSON representation of NaN or infinites
+#[test]
+fn test_transcode() {
+    let mut deserializer = Deserializer::with_options("a: NaN!\nb: 1\nc: -Infinity", options());
+    let err = deserializer.transcode(&mut Vec::new()).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Syntax { code: ErrorCode::NonFiniteFloat, line: 3, col: 4, .. },
+    ));
+    let mut deserializer = Deserializer::with_options("a: NaN!\nb: 1", options());
+    let mut json = Vec::new();
+    deserializer.transcode(&mut json).unwrap();
+    assert_eq!(String::from_utf8(json).unwrap(), r#"{"a":"NaN!","b":1}"#);
+}
+
+#[test]
+fn test_serialize() {
+    let floats = vec![f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1.0];
+    let hjson = to_string(&floats).unwrap();
+    assert_eq!(hjson, "[NaN,Infinity,-Infinity,1.0]");
+    for hjson in [hjson, to_string_pretty(&floats).unwrap()] {
+        let read: Vec<f64> = from_str_with_options(&hjson, &options()).unwrap();
+        assert!(read[0].is_nan());
+        assert_eq!(read[1..], floats[1..]);
+    }
+    // strings looking like them are quoted
+    let strings = vec!["NaN", "Infinity", "-Infinity"];
+    let hjson = to_string_pretty(&strings).unwrap();
+    assert_eq!(from_str_with_options::<Vec<String>>(&hjson, &options()).unwrap(), strings);
+}
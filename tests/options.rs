@@ -0,0 +1,181 @@
+use {
+    deser_hjson::*,
+    serde::{
+        de::{DeserializeSeed, SeqAccess, Visitor},
+        Deserialize,
+    },
+    std::fmt,
+};
+
+#[macro_use] mod common;
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Config {
+    name: String,
+    tags: Vec<String>,
+}
+
+static BRACELESS: &str = r#"
+name: "test"
+tags: ["a", "b"]
+"#;
+
+#[test]
+fn test_default_options() {
+    let options = DeserializerOptions::default();
+    let config: Config = from_str_with_options(BRACELESS, &options).unwrap();
+    assert_eq!(config.tags, vo!["a", "b"]);
+    assert!(from_str_with_options::<Config>("{name:a\ntags:[]} b", &options).is_err());
+}
+
+#[test]
+fn test_braceless_root_option() {
+    let options = DeserializerOptions::new().braceless_root(false);
+    assert!(from_str_with_options::<Config>(BRACELESS, &options).is_err());
+    let hjson = format!("{{{}}}", BRACELESS);
+    let config: Config = from_str_with_options(&hjson, &options).unwrap();
+    assert_eq!(config.name, "test");
+}
+
+#[test]
+fn test_quoteless_values_option() {
+    let options = DeserializerOptions::new().quoteless_values(false);
+    let config: Config = from_str_with_options(BRACELESS, &options).unwrap();
+    assert_eq!(config.name, "test");
+    let hjson = "name: test\ntags: []";
+    assert!(from_str::<Config>(hjson).is_ok());
+    assert!(matches!(
+        from_str_with_options::<Config>(hjson, &options),
+        Err(Error::Syntax { code: ErrorCode::Disallowed(HjsonFeature::QuotelessValues), line: 1, .. }),
+    ));
+}
+
+/// check the text is accepted by default, and rejected with the given
+/// options, with an error naming the feature at the given location
+fn check_disallowed(
+    hjson: &str,
+    options: &DeserializerOptions,
+    feature: HjsonFeature,
+    location: (usize, usize),
+) {
+    assert!(from_str::<Value>(hjson).is_ok(), "{:?} should be accepted", hjson);
+    match from_str_with_options::<Value>(hjson, options) {
+        Err(Error::Syntax { code: ErrorCode::Disallowed(f), line, col, .. }) => {
+            assert_eq!(f, feature, "wrong feature for {:?}", hjson);
+            assert_eq!((line, col), location, "wrong location for {:?}", hjson);
+        }
+        r => panic!("unexpected result for {:?}: {:?}", hjson, r),
+    }
+    let errors = Deserializer::with_options(hjson, options.clone()).validate();
+    assert!(
+        errors.iter().any(|e| matches!(
+            e,
+            Error::Syntax { code: ErrorCode::Disallowed(f), .. } if *f == feature,
+        )),
+        "validation of {:?} should find {:?}: {:?}", hjson, feature, errors,
+    );
+}
+
+#[test]
+fn test_feature_options() {
+    use HjsonFeature::*;
+    let options = DeserializerOptions::new().comments(false);
+    check_disallowed("[1, 2] # the end", &options, Comments, (1, 8));
+    check_disallowed("[1, // one\n2]", &options, Comments, (1, 5));
+    check_disallowed("{a: /* one */ 1}", &options, Comments, (1, 5));
+    assert_eq!(from_str_with_options::<String>("a/b", &options).unwrap(), "a/b");
+    let options = DeserializerOptions::new().quoteless_keys(false);
+    check_disallowed("{\"a\": 1, b: 2}", &options, QuotelessKeys, (1, 10));
+    check_disallowed("{1: 2}", &options, QuotelessKeys, (1, 2));
+    let options = DeserializerOptions::new().single_quotes(false);
+    check_disallowed("['a', \"b\"]", &options, SingleQuotes, (1, 2));
+    check_disallowed("{'a': 1}", &options, SingleQuotes, (1, 2));
+    let options = DeserializerOptions::new().multiline_strings(false);
+    check_disallowed("a:\n  '''\n  b\n  '''", &options, MultilineStrings, (2, 3));
+    let options = DeserializerOptions::new().omitted_commas(false);
+    check_disallowed("[\n  1\n  2\n]", &options, OmittedCommas, (3, 3));
+    check_disallowed("{\n  a: 1\n  b: 2\n}", &options, OmittedCommas, (3, 3));
+    check_disallowed("a: 1\nb: 2", &options, OmittedCommas, (2, 1));
+    assert!(from_str_with_options::<Value>("a: 1,\nb: 2\n", &options).is_ok());
+    let options = DeserializerOptions::new().trailing_commas(false);
+    check_disallowed("[1, 2, ]", &options, TrailingCommas, (1, 6));
+    check_disallowed("{a: 1,\n}", &options, TrailingCommas, (1, 6));
+    check_disallowed("a: 1\nb: 2,\n", &options, TrailingCommas, (2, 5));
+    assert!(from_str_with_options::<Value>("[1\n2]", &options).is_ok());
+    let options = DeserializerOptions::new().braceless_root(false).quoteless_values(false);
+    check_disallowed("a: 1", &options, BracelessRoot, (1, 1));
+    let options = DeserializerOptions::new().braceless_root(false);
+    assert!(matches!(
+        from_str_with_options::<Config>(BRACELESS, &options),
+        Err(Error::Syntax { code: ErrorCode::Disallowed(BracelessRoot), line: 2, col: 1, .. }),
+    ));
+}
+
+#[test]
+fn test_strict_json() {
+    let options = DeserializerOptions::strict_json();
+    let json = r#"{
+        "name": "test",
+        "tags": ["a", "b"],
+        "nested": {"a": [1, -2.5e3, true, null, {}, []]}
+    }"#;
+    let value: Value = from_str_with_options(json, &options).unwrap();
+    if cfg!(not(feature = "arbitrary_precision")) { // or -2.5e3 is kept as text
+        assert_eq!(value, serde_json::from_str::<Value>(json).unwrap());
+    }
+    let config: Config = from_str_with_options(json, &options).unwrap();
+    assert_eq!(config.tags, vo!["a", "b"]);
+    assert!(Deserializer::with_options(json, options.clone()).validate().is_empty());
+    for hjson in [
+        BRACELESS,
+        "{name: \"test\", tags: []}",
+        "{\"name\": test\n\"tags\": []}",
+        "{\"name\": \"test\"\n\"tags\": []}",
+        "{\"name\": \"test\", \"tags\": [],}",
+        "{\"name\": 'test', \"tags\": []}",
+        "{\"name\": \"test\", \"tags\": []} // the end",
+    ] {
+        assert!(from_str::<Config>(hjson).is_ok(), "{:?} should be accepted", hjson);
+        assert!(from_str_with_options::<Config>(hjson, &options).is_err());
+    }
+}
+
+#[test]
+fn test_trailing_chars_option() {
+    let options = DeserializerOptions::new().trailing_chars(TrailingChars::Ignore);
+    let v: Vec<u8> = from_str_with_options("[1, 2] [3]", &options).unwrap();
+    assert_eq!(v, vec![1, 2]);
+}
+
+/// Check the deserializer can be driven from outside the crate,
+/// here with a seed summing the numbers of an array
+#[test]
+fn test_deserialize_seed() {
+    struct Sum;
+    impl<'de> DeserializeSeed<'de> for Sum {
+        type Value = u64;
+        fn deserialize<D>(self, deserializer: D) -> std::result::Result<u64, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            deserializer.deserialize_seq(self)
+        }
+    }
+    impl<'de> Visitor<'de> for Sum {
+        type Value = u64;
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an array of numbers")
+        }
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<u64, A::Error> {
+            let mut sum = 0;
+            while let Some(n) = seq.next_element::<u64>()? {
+                sum += n;
+            }
+            Ok(sum)
+        }
+    }
+    let mut deserializer = Deserializer::from_str("[1, 2, 3] // the end");
+    let sum = Sum.deserialize(&mut deserializer).unwrap();
+    deserializer.check_all_consumed().unwrap();
+    assert_eq!(sum, 6);
+}
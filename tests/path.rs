@@ -0,0 +1,112 @@
+use {
+    deser_hjson::*,
+    serde::Deserialize,
+    std::collections::HashMap,
+};
+
+#[macro_use] mod common;
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Listen {
+    host: String,
+    port: u16,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Server {
+    name: String,
+    listen: Listen,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+enum Action {
+    Wait(u32),
+    Move { x: i32, y: i32 },
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Config {
+    servers: Vec<Server>,
+    #[serde(default)]
+    labels: HashMap<String, u8>,
+    #[serde(default)]
+    actions: Vec<Action>,
+}
+
+fn error_path(hjson: &str) -> String {
+    let err = from_str::<Config>(hjson).unwrap_err();
+    err.path()
+        .unwrap_or_else(|| panic!("no path in {:?}", err))
+        .to_string()
+}
+
+#[test]
+fn test_error_paths() {
+    let servers = r#"
+        servers: [
+            { name: "a", listen: { host: "localhost", port: 80 } }
+            {
+                name: b
+                listen: {
+                    host: localhost
+                    port: 81
+                }
+            }
+            {
+                name: c
+                listen: {
+                    host: localhost
+                    port: "not a port"
+                }
+            }
+        ]
+    "#;
+    assert_eq!(error_path(servers), "servers[2].listen.port");
+    let err = from_str::<Config>(servers).unwrap_err();
+    assert!(err.to_string().starts_with("servers[2].listen.port: "));
+    assert_eq!(
+        error_path("servers: [\n{\nname: a\nlisten: {\nhost: h\n}\n}\n]"),
+        "servers[0].listen",
+    );
+    assert_eq!(
+        error_path("servers: []\nlabels: {\n\"a key\": 1\n'b.c': 300\n}"),
+        r#"labels["b.c"]"#,
+    );
+    assert_eq!(
+        error_path("servers: []\nactions: [\n{ Wait: 3 }\n{ Move: { x: 1, y: no } }\n]"),
+        "actions[1].Move.y",
+    );
+}
+
+#[test]
+fn test_no_raw_error_at_root() {
+    let err = from_str::<Action>("Jump").unwrap_err();
+    assert!(matches!(err, Error::Serde { line: 1, .. }), "{:?}", err);
+}
+
+#[test]
+fn test_path_parsing() {
+    for s in &[
+        "",
+        "a",
+        "servers[2].listen.port",
+        "[0][1]",
+        r#"labels["b.c"].x"#,
+        r#"a["with \"quotes\""]"#,
+    ] {
+        let path: Path = s.parse().unwrap();
+        assert_eq!(&path.to_string(), s);
+    }
+    let path: Path = r#"a["b"]"#.parse().unwrap();
+    assert_eq!(path.to_string(), "a.b");
+    assert_eq!(path.parent().unwrap().to_string(), "a");
+    assert!("a..b".parse::<Path>().is_err());
+    assert!("a.".parse::<Path>().is_err());
+    assert!("a[b]".parse::<Path>().is_err());
+    assert!("a[1".parse::<Path>().is_err());
+    assert!("a[1]b".parse::<Path>().is_err());
+}
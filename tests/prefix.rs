@@ -0,0 +1,63 @@
+use {
+    deser_hjson::*,
+    serde::Deserialize,
+    std::collections::HashMap,
+};
+
+#[macro_use] mod common;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Meta {
+    title: String,
+    draft: bool,
+}
+
+fn prefix<'de, T: Deserialize<'de>>(text: &'de str) -> (T, &'de str) {
+    let (value, end) = from_str_prefix(text).unwrap();
+    (value, &text[end..])
+}
+
+#[test]
+fn test_braceless_prefix() {
+    let expected = Meta { title: "A title".to_string(), draft: false };
+    let (meta, rest) = prefix::<Meta>("title: A title\ndraft: false\n---\nthe rest\n");
+    assert_eq!(meta, expected);
+    assert_eq!(rest, "the rest\n");
+    let (meta, rest) = prefix::<Meta>("---\n# meta\ntitle: A title\ndraft: false\n  ---  \n\nthe rest");
+    assert_eq!(meta, expected);
+    assert_eq!(rest, "\nthe rest");
+    // without delimiter, the braceless map goes to the end
+    let (map, rest) = prefix::<HashMap<String, u8>>("a: 1\nb: 2\n\n");
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["b"], 2);
+    assert_eq!(rest, "\n\n");
+    // a delimiter needs its own line
+    let (map, rest) = prefix::<HashMap<String, String>>("a: ---\nb: x --- y\n---");
+    assert_eq!(map["a"], "---");
+    assert_eq!(map["b"], "x --- y");
+    assert_eq!(rest, "");
+}
+
+#[test]
+fn test_braced_prefix() {
+    let (v, rest) = prefix::<Vec<u8>>("[1, 2] and the rest");
+    assert_eq!(v, vec![1, 2]);
+    assert_eq!(rest, " and the rest");
+    let (map, rest) = prefix::<HashMap<String, u8>>("{\n  a: 1\n}\n// comment\n---\nthe rest");
+    assert_eq!(map["a"], 1);
+    assert_eq!(rest, "the rest");
+    let (s, rest) = prefix::<String>("\"a string\"\nthe rest");
+    assert_eq!(s, "a string");
+    assert_eq!(rest, "\nthe rest");
+}
+
+#[test]
+fn test_prefix_errors() {
+    let err = from_str_prefix::<Meta>("title: A title\ndraft: maybe\n---\n").unwrap_err();
+    assert_eq!(err.location(), Some((2, 8)));
+    assert!(from_str_prefix::<Meta>("title: A title\n---\ndraft: false\n").is_err());
+    // from_str doesn't accept anything after the delimiter
+    let options = DeserializerOptions::new().root_delimiter("---");
+    assert!(from_str_with_options::<Value>("a: 1\n---\nb: 2", &options).is_err());
+    assert!(from_str_with_options::<Value>("a: 1\n---\nb: 2", &DeserializerOptions::new()).is_err());
+}
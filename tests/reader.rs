@@ -1,10 +1,56 @@
 use {
-    deser_hjson::from_reader,
+    deser_hjson::*,
     serde:: Deserialize,
+    std::{
+        collections::HashMap,
+        fmt::Debug,
+        fs,
+        io::{self, Read},
+        path::Path,
+    },
 };
 
 #[macro_use] mod common;
 
+/// a reader giving its bytes one at a time
+struct Trickle<'a> {
+    bytes: &'a [u8],
+    /// after how many bytes an error is raised, if any
+    fail_at: Option<usize>,
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.fail_at == Some(0) {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"));
+        }
+        self.fail_at = self.fail_at.map(|n| n - 1);
+        match self.bytes.split_first() {
+            Some((&b, rest)) if !buf.is_empty() => {
+                buf[0] = b;
+                self.bytes = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+fn trickle(s: &str) -> Trickle<'_> {
+    Trickle { bytes: s.as_bytes(), fail_at: None }
+}
+
+/// check the reader gives the same result, or the same error,
+/// than the str
+fn check_same<T>(hjson: &str)
+where
+    T: serde::de::DeserializeOwned + Debug,
+{
+    let from_str = format!("{:?}", from_str::<T>(hjson));
+    let from_reader = format!("{:?}", from_reader::<_, T>(trickle(hjson)));
+    assert_eq!(from_str, from_reader, "in {:?}", hjson);
+}
+
 #[test]
 fn test_reader() {
     #[derive(Deserialize, PartialEq, Debug)]
@@ -19,3 +65,92 @@ fn test_reader() {
     };
     assert_eq!(expected, from_reader(&hjson[..]).unwrap());
 }
+
+#[test]
+fn test_same_as_str() {
+    for hjson in &[
+        "",
+        "a: 1\nb: [1, 2, {c: 3}]\n",
+        "{\n  a: '''\n    multi\n    line\n    '''\n  b: \"x\\ny\"\n}",
+        "[\n  /* a comment\n  on several lines */ 1\n  2 # end\n]",
+        "[1, 2 /* unclosed\n\n",
+        "a: 1\nb: [1,\n  2\n",
+        "a: 1\nb 2\n",
+        "a: {\n  b: {\n    c: [1, 2, 3}\n  }\n}",
+        "a: \"unclosed\nb: 2",
+        "a: 'é\\q'",
+        "[1, 2] 3",
+        "a: 1 // comment without newline",
+    ] {
+        check_same::<Value>(hjson);
+    }
+    // tokens and spaces longer than what's read ahead
+    let spaces = " ".repeat(100);
+    check_same::<Value>(&format!("[\n  true{}x\n  false{}\n]", spaces, spaces));
+    check_same::<Value>(&format!("a: 0.{}1\nb: 1{}// comment", "0".repeat(100), spaces));
+    check_same::<Value>(&format!("{{ {}: 1 }}", "k".repeat(100)));
+    check_same::<HashMap<String, Vec<u8>>>("a: [1, 2]\nb: [3, 300]");
+    check_same::<Vec<Spanned<String>>>("[\n  a\n  'b'\n  '''\n  c\n  '''\n]");
+}
+
+#[test]
+fn test_hjson_suite_same_as_str() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/hjson-test-suite");
+    for entry in fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.to_string_lossy().contains("_test.") {
+            check_same::<Value>(&fs::read_to_string(path).unwrap());
+        }
+    }
+}
+
+#[test]
+fn test_big_document() {
+    let mut hjson = String::from("{\n  outer: {\n");
+    for i in 0..10_000 {
+        hjson.push_str(&format!("    'key {}': {}\n", i, i));
+    }
+    hjson.push_str("    last: oops\n  }\n}\n");
+    assert!(hjson.len() > 200_000);
+    let err = from_reader::<_, HashMap<String, HashMap<String, u32>>>(trickle(&hjson)).unwrap_err();
+    assert_eq!(err.path().unwrap().to_string(), "outer.last");
+    check_same::<HashMap<String, HashMap<String, u32>>>(&hjson);
+    let ok = hjson.replace("oops", "10000");
+    let map: HashMap<String, HashMap<String, u32>> = from_reader(ok.as_bytes()).unwrap();
+    assert_eq!(map["outer"]["key 9999"], 9999);
+    check_same::<Value>(&hjson);
+}
+
+#[test]
+fn test_reading_errors() {
+    let bytes = b"a: 1\nb: [\n  x\n  \xC3\x28\n]\n";
+    let sliced = format!("{:?}", from_slice::<Value>(bytes));
+    let read = format!("{:?}", from_reader::<_, Value>(&bytes[..]));
+    assert!(sliced.starts_with("Err(Utf8("));
+    assert_eq!(sliced, read);
+    let hjson = "a: 1\nb: 2\n";
+    for fail_at in 0..hjson.len() {
+        let reader = Trickle { bytes: hjson.as_bytes(), fail_at: Some(fail_at) };
+        let err = from_reader::<_, Value>(reader).unwrap_err();
+        assert!(matches!(err, Error::Io(_)), "{:?}", err);
+    }
+}
+
+/// check a document on a single line, bigger than the text which is
+/// kept once parsed, is read the same as from a str
+#[test]
+fn test_long_line() {
+    let mut hjson = String::from("[");
+    for i in 0..20_000 {
+        hjson.push_str(&format!("{{\"id\":{},\"name\":\"item {}\",\"ok\":true}},", i, i));
+    }
+    hjson.push_str("{\"id\":]}]");
+    assert!(hjson.len() > 500_000);
+    let err = from_reader::<_, Value>(trickle(&hjson)).unwrap_err();
+    assert_eq!(err.location(), Some((1, hjson.len() - 2)));
+    check_same::<Value>(&hjson);
+    let ok = hjson.replace("\"id\":]", "\"id\":20000");
+    let value: Value = from_reader(ok.as_bytes()).unwrap();
+    assert_eq!(value[20_000]["id"].as_u64(), Some(20_000));
+    check_same::<Value>(&ok);
+}
@@ -0,0 +1,120 @@
+use {
+    deser_hjson::{*, ErrorCode::*, edit::Document},
+    serde::Deserialize,
+};
+
+#[macro_use] mod common;
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+enum Tree {
+    Leaf(u8),
+    Node(Box<Tree>),
+}
+
+fn nested_arrays(depth: usize) -> String {
+    format!("{}{}", "[".repeat(depth), "]".repeat(depth))
+}
+
+fn nested_enums(depth: usize) -> String {
+    format!("{}{{Leaf: 1}}{}", "{Node: ".repeat(depth), "}".repeat(depth))
+}
+
+fn assert_limit_exceeded(err: Error, expected_col: usize) {
+    match err {
+        Error::Syntax { code: RecursionLimitExceeded, line: 1, col, .. } => {
+            assert_eq!(col, expected_col);
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn test_default_limit() {
+    from_str::<Value>(&nested_arrays(128)).unwrap();
+    let err = from_str::<Value>(&nested_arrays(129)).unwrap_err();
+    assert_limit_exceeded(err, 129);
+    // this would overflow the stack without a limit
+    let err = from_str::<Value>(&"[".repeat(100_000)).unwrap_err();
+    assert_limit_exceeded(err, 129);
+    let err = from_str::<Value>(&"{a:".repeat(100_000)).unwrap_err();
+    assert_limit_exceeded(err, 3 * 128 + 1);
+    from_str::<Tree>(&nested_enums(127)).unwrap();
+    let err = from_str::<Tree>(&nested_enums(200)).unwrap_err();
+    assert_limit_exceeded(err, 7 * 128 + 1);
+}
+
+#[test]
+fn test_limit_in_validation_and_edition() {
+    let errors = validate_str(&"[".repeat(100_000));
+    assert!(matches!(errors[0], Error::Syntax { code: RecursionLimitExceeded, .. }));
+    assert!(validate_str(&nested_arrays(128)).is_empty());
+    let err = Document::parse(&"[".repeat(100_000)).unwrap_err();
+    assert_limit_exceeded(err, 129);
+}
+
+#[test]
+fn test_custom_limit() {
+    let options = DeserializerOptions::new().recursion_limit(2);
+    from_str_with_options::<Vec<Vec<u8>>>("[[1], [2, 3]]", &options).unwrap();
+    let err = from_str_with_options::<Value>("[[[1]]]", &options).unwrap_err();
+    assert_limit_exceeded(err, 3);
+    // the limit applies to the depth, not to the number of containers
+    let hjson = "a: [1]\nb: [2]\nc: {d: 3}";
+    from_str_with_options::<Value>(hjson, &options).unwrap();
+}
+
+#[test]
+fn test_disabled_limit() {
+    // a bigger stack is needed for this depth in debug builds
+    std::thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(|| {
+            let options = DeserializerOptions::new().disable_recursion_limit();
+            let hjson = nested_arrays(1000);
+            assert!(from_str::<Value>(&hjson).is_err());
+            from_str_with_options::<Value>(&hjson, &options).unwrap();
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn test_limit_in_braceless_root() {
+    #[derive(Deserialize, Debug)]
+    #[serde(tag = "type")]
+    #[allow(dead_code)]
+    enum Tagged {
+        A { x: Vec<u8> },
+    }
+    #[derive(Deserialize, Debug)]
+    #[serde(untagged)]
+    #[allow(dead_code)]
+    enum Untagged {
+        N(u8),
+        M { x: Vec<u8> },
+    }
+    // the braceless root map counts as a level
+    let options = DeserializerOptions::new().recursion_limit(2);
+    let hjson = "type: A\nx: [1]";
+    from_str_with_options::<Tagged>(hjson, &options).unwrap();
+    from_str_with_options::<Untagged>("x: [1]", &options).unwrap();
+    let options = DeserializerOptions::new().recursion_limit(1);
+    from_str_with_options::<Value>("a: 1", &options).unwrap();
+    let err = from_str_with_options::<Tagged>(hjson, &options).unwrap_err();
+    assert!(matches!(err, Error::Syntax { code: RecursionLimitExceeded, line: 2, col: 4, .. }));
+    let err = from_str_with_options::<Untagged>("x: [1]", &options).unwrap_err();
+    assert_limit_exceeded(err, 4);
+    let err = from_str_with_options::<Value>("a: [1]", &options).unwrap_err();
+    assert_limit_exceeded(err, 4);
+    let errors = Deserializer::with_options("a: [1]", options.clone()).validate();
+    assert!(matches!(errors[0], Error::Syntax { code: RecursionLimitExceeded, col: 4, .. }));
+    let err = Deserializer::with_options("a: [1]", options.clone())
+        .transcode(Vec::new())
+        .unwrap_err();
+    assert_limit_exceeded(err, 4);
+    let options = DeserializerOptions::new().recursion_limit(0);
+    let err = from_str_with_options::<Untagged>("x: [1]", &options).unwrap_err();
+    assert_limit_exceeded(err, 1);
+}
@@ -0,0 +1,66 @@
+use {
+    deser_hjson::*,
+    serde::Deserialize,
+};
+
+#[macro_use] mod common;
+
+#[test]
+fn test_render_first_line() {
+    let src = "[1, 2, 3}\n";
+    let err = from_str::<Vec<u8>>(src).unwrap_err();
+    assert_eq!(
+        err.render(src, None),
+        "\
+error: [3]: expected an integer between 0 and 255
+ --> <input>:1:9
+  |
+1 | [1, 2, 3}
+  |         ^
+",
+    );
+}
+
+#[test]
+fn test_render_tabs() {
+    let src = "{\n\ta: 1\n\tb: \"\\q\"\n}";
+    let err = from_str::<Value>(src).unwrap_err();
+    let rendered = err.render(src, Some("test.hjson"));
+    assert!(rendered.starts_with("error: b: invalid escape sequence\n --> test.hjson:3:6\n"));
+    assert!(rendered.contains("\n3 | \tb: \"\\q\"\n  | \t    ^\n"));
+}
+
+#[test]
+fn test_render_serde_error() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Config {
+        port: u16,
+    }
+    let src = "\n\n\nname: test\n";
+    let err = from_str::<Config>(src).unwrap_err();
+    let rendered = err.render(src, Some("test.hjson"));
+    assert!(rendered.starts_with("error: missing field `port`\n"));
+    assert!(rendered.contains("-->"));
+}
+
+#[test]
+fn test_render_many_lines() {
+    let mut src = String::new();
+    for i in 0..12 {
+        src.push_str(&format!("key{}: {}\n", i, i));
+    }
+    src.push_str("key12 12\n");
+    let err = from_str::<Value>(&src).unwrap_err();
+    assert_eq!(
+        err.render(&src, None),
+        "\
+error: key12: expected a colon after the map key
+  --> <input>:13:7
+   |
+12 | key11: 11
+13 | key12 12
+   |       ^
+",
+    );
+}
@@ -0,0 +1,200 @@
+use {
+    deser_hjson::{from_str, to_string, to_string_pretty, Value},
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+#[macro_use] mod common;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Point(i32, i32),
+    Rect { width: u16, height: u16 },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Config {
+    name: String,
+    port: u16,
+    ratio: f64,
+    enabled: bool,
+    nothing: Option<i8>,
+    tags: Vec<String>,
+    description: String,
+    shapes: Vec<Shape>,
+    limits: BTreeMap<String, i64>,
+    nested: Vec<Vec<u8>>,
+}
+
+fn config() -> Config {
+    let mut limits = BTreeMap::new();
+    limits.insert("min".to_string(), -5);
+    limits.insert("max value".to_string(), 3_000_000_000);
+    Config {
+        name: "My Server: the best one".to_string(),
+        port: 8080,
+        ratio: 2.0,
+        enabled: true,
+        nothing: None,
+        tags: vo![
+            "simple",
+            "",
+            " spaced ",
+            "true",
+            "null but not really",
+            "42",
+            "-3",
+            "# not a comment",
+            "// not a comment either",
+            "{",
+            "with \"quotes\" and 'apostrophes'",
+            "'''",
+            "tab\there",
+            "ends with newline\n",
+            "bell\x07",
+        ],
+        description: "A multiline text:\n  indented line\n\nafter an empty line".to_string(),
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(0.5),
+            Shape::Point(-3, 4),
+            Shape::Rect { width: 3, height: 4 },
+        ],
+        limits,
+        nested: vec![vec![], vec![1, 2]],
+    }
+}
+
+#[test]
+fn test_pretty_round_trip() {
+    let config = config();
+    let hjson = to_string_pretty(&config).unwrap();
+    let read: Config = from_str(&hjson)
+        .unwrap_or_else(|e| panic!("Parsing failed for {}\n{}", hjson, e));
+    assert_eq!(read, config);
+}
+
+#[test]
+fn test_compact_round_trip() {
+    let config = config();
+    let hjson = to_string(&config).unwrap();
+    assert!(!hjson.contains('\n'));
+    let read: Config = from_str(&hjson)
+        .unwrap_or_else(|e| panic!("Parsing failed for {}\n{}", hjson, e));
+    assert_eq!(read, config);
+}
+
+/// a root string with newlines is written at the very start of the text
+#[test]
+fn test_root_multiline_string_round_trip() {
+    for s in ["a\nb", "line 1\n  indented\n\nlast"] {
+        let hjson = to_string(&s).unwrap();
+        assert_eq!(from_str::<String>(&hjson).unwrap(), s);
+        let hjson = to_string_pretty(&s).unwrap();
+        assert!(hjson.starts_with("'''"));
+        assert_eq!(from_str::<String>(&hjson).unwrap(), s, "read from {:?}", hjson);
+    }
+}
+
+/// strings which would be read differently without quotes
+#[test]
+fn test_quoted_strings_round_trip() {
+    let strings = [
+        "*.rs",
+        "*",
+        "url: http://x",
+        "a:b",
+        "key : value",
+        "a b: c",
+    ];
+    for s in strings {
+        let hjson = to_string_pretty(&s).unwrap();
+        assert_eq!(from_str::<String>(&hjson).unwrap(), s, "read from {:?}", hjson);
+        let hjson = to_string_pretty(&vec![s]).unwrap();
+        assert_eq!(from_str::<Vec<String>>(&hjson).unwrap(), vec![s], "read from {:?}", hjson);
+        let value = Value::String(s.to_string());
+        let hjson = to_string_pretty(&value).unwrap();
+        assert_eq!(from_str::<Value>(&hjson).unwrap(), value, "read from {:?}", hjson);
+    }
+    // within a map or an array, a colon doesn't need quotes
+    assert_eq!(to_string_pretty(&vec!["url: http://x"]).unwrap(), "[\n  url: http://x\n]");
+}
+
+#[test]
+fn test_pretty_output() {
+    #[derive(Serialize)]
+    struct Example {
+        key: i32,
+        contains: String,
+        cool: BTreeMap<&'static str, u16>,
+        list: Vec<usize>,
+        empty: Vec<usize>,
+        realist: String,
+        missing: Option<f64>,
+    }
+    let mut cool = BTreeMap::new();
+    cool.insert("bar", 2);
+    cool.insert("foo", 1);
+    let example = Example {
+        key: 1,
+        contains: "everything on this line".to_string(),
+        cool,
+        list: vec![1, 2],
+        empty: vec![],
+        realist: "My half empty glass,\nI will fill your empty half.\nNow you are half full.".to_string(),
+        missing: None,
+    };
+    assert_eq!(
+        to_string_pretty(&example).unwrap(),
+        r#"{
+  key: 1
+  contains: everything on this line
+  cool: {
+    bar: 2
+    foo: 1
+  }
+  list: [
+    1
+    2
+  ]
+  empty: []
+  realist:
+    '''
+    My half empty glass,
+    I will fill your empty half.
+    Now you are half full.
+    '''
+  missing: null
+}"#
+    );
+}
+
+#[test]
+fn test_enum_output() {
+    assert_eq!(to_string_pretty(&Shape::Empty).unwrap(), "Empty");
+    assert_eq!(
+        to_string_pretty(&Shape::Point(1, 2)).unwrap(),
+        "{\n  Point: [\n    1\n    2\n  ]\n}"
+    );
+    assert_eq!(to_string(&Shape::Circle(1.5)).unwrap(), "{Circle:1.5}");
+    assert_eq!(
+        to_string(&Shape::Rect { width: 1, height: 2 }).unwrap(),
+        "{Rect:{width:1,height:2}}"
+    );
+}
+
+#[test]
+fn test_non_string_keys() {
+    let mut map = BTreeMap::new();
+    map.insert(3u32, "three");
+    map.insert(10u32, "ten");
+    let hjson = to_string_pretty(&map).unwrap();
+    assert_eq!(hjson, "{\n  3: three\n  10: ten\n}");
+    let read: BTreeMap<u32, String> = from_str(&hjson).unwrap();
+    assert_eq!(read.get(&10).map(|s| s.as_str()), Some("ten"));
+    let mut map = BTreeMap::new();
+    map.insert(vec![1], 1);
+    assert!(to_string(&map).is_err());
+}
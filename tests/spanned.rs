@@ -0,0 +1,95 @@
+use {
+    deser_hjson::*,
+    serde::Deserialize,
+    std::collections::HashMap,
+};
+
+#[macro_use] mod common;
+
+#[derive(Deserialize, Debug)]
+struct Server {
+    host: Spanned<String>,
+    port: Spanned<u16>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Config {
+    name: Spanned<String>,
+    servers: Spanned<Vec<Server>>,
+    limits: HashMap<String, Spanned<f64>>,
+    motto: Spanned<String>,
+}
+
+static HJSON: &str = r#"
+# a config
+name: my config
+servers: [
+    {
+        host: "localhost"
+        port: 8080
+    }
+    {
+        host: example.com   # not a comment
+        port: 80 // a comment
+    }
+]
+limits: {
+	speed: 0.5
+}
+motto:
+    '''
+    two
+    lines
+    '''
+"#;
+
+#[test]
+fn test_spanned() {
+    let config: Config = from_str(HJSON).unwrap();
+    let check = |start: usize, end: usize, line: usize, col: usize, text: &str| {
+        assert_eq!(&HJSON[start..end], text);
+        let (l, c) = HJSON[..start].lines().enumerate().last()
+            .map(|(i, s)| (i + 1, s.chars().count() + 1))
+            .unwrap();
+        assert_eq!((line, col), (l, c), "location of {:?}", text);
+    };
+    let name = &config.name;
+    check(name.start(), name.end(), name.line(), name.col(), "my config");
+    assert_eq!(name.get_ref(), "my config");
+    let servers = &config.servers;
+    assert_eq!(servers.line(), 4);
+    assert_eq!(servers.col(), 10);
+    assert!(HJSON[servers.span()].starts_with('['));
+    assert!(HJSON[servers.span()].ends_with(']'));
+    let host = &servers.get_ref()[0].host;
+    check(host.start(), host.end(), host.line(), host.col(), "\"localhost\"");
+    let host = &servers.get_ref()[1].host;
+    check(host.start(), host.end(), host.line(), host.col(), "example.com   # not a comment");
+    let port = &servers.get_ref()[1].port;
+    check(port.start(), port.end(), port.line(), port.col(), "80");
+    assert_eq!(*port.get_ref(), 80);
+    let speed = &config.limits["speed"];
+    check(speed.start(), speed.end(), speed.line(), speed.col(), "0.5");
+    assert_eq!(speed.col(), 9); // tab is one char
+    let motto = &config.motto;
+    assert_eq!(motto.line(), 18);
+    assert!(HJSON[motto.span()].starts_with("'''"));
+    assert!(HJSON[motto.span()].ends_with("'''"));
+    assert_eq!(motto.clone().into_inner(), "two\nlines");
+}
+
+#[test]
+fn test_spanned_root() {
+    let root: Spanned<HashMap<String, u8>> = from_str("\n  a: 1\n  b: 2\n").unwrap();
+    assert_eq!(root.line(), 2);
+    assert_eq!(root.col(), 3);
+    assert_eq!(root.get_ref()["b"], 2);
+    let root: Spanned<Vec<u8>> = from_str(" [1, 2] ").unwrap();
+    assert_eq!(root.span(), 1..7);
+}
+
+#[test]
+fn test_spanned_serialization() {
+    let root: Spanned<Vec<u8>> = from_str("[1, 2]").unwrap();
+    assert_eq!(to_string(&root).unwrap(), "[1,2]");
+}
@@ -0,0 +1,123 @@
+use {
+    deser_hjson::*,
+    serde::Deserialize,
+    std::io::{self, Read},
+};
+
+#[macro_use] mod common;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Record {
+    id: u32,
+    msg: String,
+}
+
+fn record(id: u32, msg: &str) -> Record {
+    Record { id, msg: msg.to_string() }
+}
+
+#[test]
+fn test_concatenated_values() {
+    let hjson = "{id:1,msg:\"a\"}{id:2,msg:\"b\"}\n\n# a comment\n{\n  id: 3\n  msg: c\n}\n";
+    let records: Vec<Record> = Deserializer::from_str(hjson)
+        .into_iter()
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(records, vec![record(1, "a"), record(2, "b"), record(3, "c")]);
+    let values: Vec<Value> = Deserializer::from_str("1 // one\n\"two\" [3] three\n")
+        .into_iter()
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(values, vec![
+        Value::from(1u64),
+        Value::from("two"),
+        Value::Array(vec![Value::from(3u64)]),
+        Value::from("three"),
+    ]);
+    assert_eq!(Deserializer::from_str("").into_iter::<Value>().count(), 0);
+    assert_eq!(Deserializer::from_str(" // nothing").into_iter::<Value>().count(), 0);
+}
+
+#[test]
+fn test_offsets() {
+    let hjson = "{id:1,msg:\"a\"}  {id:2,msg:\"b\"}\n";
+    let mut stream = Deserializer::from_str(hjson).into_iter::<Record>();
+    assert_eq!(stream.byte_offset(), 0);
+    stream.next().unwrap().unwrap();
+    assert_eq!(stream.value_offset(), 0);
+    assert_eq!(stream.byte_offset(), 14);
+    stream.next().unwrap().unwrap();
+    assert_eq!(stream.value_offset(), 16);
+    assert_eq!(stream.byte_offset(), 30);
+    assert!(stream.next().is_none());
+    assert_eq!(stream.byte_offset(), 30);
+}
+
+#[test]
+fn test_error_in_a_record() {
+    let hjson = "{id:1,msg:\"a\"}\n{id:x,msg:\"b\"}\n{id:3,msg:\"c\"}\n";
+    let mut stream = Deserializer::from_str(hjson).into_iter::<Record>();
+    assert_eq!(stream.next().unwrap().unwrap(), record(1, "a"));
+    let err = stream.next().unwrap().unwrap_err();
+    assert_eq!(err.location(), Some((2, 5)));
+    assert_eq!(err.path().unwrap().to_string(), "id");
+    assert_eq!(stream.byte_offset(), 14);
+    assert!(stream.next().is_none());
+    // an unclosed comment is an error, not the end of the stream
+    let mut stream = Deserializer::from_str("[1] /* unclosed").into_iter::<Value>();
+    assert!(stream.next().unwrap().is_ok());
+    assert!(stream.next().unwrap().is_err());
+}
+
+#[test]
+fn test_stream_from_reader() {
+    let mut hjson = String::new();
+    for id in 0..5_000 {
+        hjson.push_str(&format!("{{ id: {}, msg: \"record {}\" }}\n", id, id));
+    }
+    let stream = Deserializer::from_reader(hjson.as_bytes()).into_iter::<Record>();
+    let mut count = 0;
+    for (id, record) in stream.enumerate() {
+        assert_eq!(record.unwrap().id as usize, id);
+        count += 1;
+    }
+    assert_eq!(count, 5_000);
+}
+
+/// a reader giving the bytes of a text, then failing
+struct Failing<'a> {
+    bytes: &'a [u8],
+}
+
+impl Read for Failing<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.bytes.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"));
+        }
+        let len = buf.len().min(self.bytes.len()).min(3);
+        buf[..len].copy_from_slice(&self.bytes[..len]);
+        self.bytes = &self.bytes[len..];
+        Ok(len)
+    }
+}
+
+/// a reading error, even after the last record, is reported
+/// instead of looking like the end of the stream
+#[test]
+fn test_reading_error() {
+    let hjson = "{id:1,msg:a}\n{id:2,msg:b}\n";
+    for len in 0..=hjson.len() {
+        let reader = io::BufReader::new(Failing { bytes: &hjson.as_bytes()[..len] });
+        let mut stream = Deserializer::from_reader(reader).into_iter::<Record>();
+        let mut ids = Vec::new();
+        loop {
+            match stream.next() {
+                Some(Ok(record)) => ids.push(record.id),
+                Some(Err(Error::Io(_))) => break,
+                r => panic!("unexpected {:?} after reading {} bytes", r, len),
+            }
+        }
+        assert!(stream.next().is_none());
+        assert!(ids.len() <= 2 && ids.iter().enumerate().all(|(i, &id)| id as usize == i + 1));
+    }
+}
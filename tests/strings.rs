@@ -1,5 +1,5 @@
 use {
-    deser_hjson::from_str,
+    deser_hjson::{from_str, Error, ErrorCode},
     serde:: Deserialize,
     std::collections::HashMap,
 };
@@ -53,3 +53,46 @@ fn test_weird_map_keys() {
     };
     assert_eq!(value, from_str(hjson).unwrap());
 }
+
+#[test]
+fn test_colons_in_quoteless_values() {
+    let hjson = r#"
+        url: http://example.com:8080/path
+        time: 12:30 // not a comment
+    "#;
+    let map: HashMap<String, String> = from_str(hjson).unwrap();
+    assert_eq!(map["url"], "http://example.com:8080/path");
+    assert_eq!(map["time"], "12:30 // not a comment");
+    // the key type doesn't change how the value is read
+    let map: HashMap<u16, String> = from_str("8080: localhost:8080\n443: a:b").unwrap();
+    assert_eq!(map[&8080], "localhost:8080");
+    assert_eq!(map[&443], "a:b");
+    let map: HashMap<bool, String> = from_str("true: yes:sure\nfalse : no:way").unwrap();
+    assert_eq!(map[&true], "yes:sure");
+    assert_eq!(map[&false], "no:way");
+    let map: HashMap<char, String> = from_str("{\n  a: b:c\n}").unwrap();
+    assert_eq!(map[&'a'], "b:c");
+}
+
+#[test]
+fn test_unicode_escapes() {
+    assert_eq!(from_str::<String>(r#""\u00e9t\u00E9""#).unwrap(), "été");
+    // astral chars are escaped as UTF-16 surrogate pairs
+    assert_eq!(from_str::<String>(r#""smile: \ud83d\ude00!""#).unwrap(), "smile: 😀!");
+    assert_eq!(from_str::<String>(r#"'\uD834\uDD1E'"#).unwrap(), "𝄞");
+    assert_eq!(from_str::<String>("'𝄞'").unwrap(), "𝄞");
+    let lone = |hjson: &str| match from_str::<String>(hjson) {
+        Err(Error::Syntax { code: ErrorCode::LoneSurrogate, col, .. }) => col,
+        r => panic!("unexpected result for {:?}: {:?}", hjson, r),
+    };
+    assert_eq!(lone(r#""a\ud83d""#), 3);
+    assert_eq!(lone(r#""a\ud83d b""#), 3);
+    assert_eq!(lone(r#""a\ud83dA""#), 3);
+    assert_eq!(lone(r#""\ude00\ud83d""#), 2);
+    for hjson in &[r#""\u12""#, r#""\u+123""#, r#""\u00é""#, r#""\ud83d\u12""#] {
+        assert!(matches!(
+            from_str::<String>(hjson),
+            Err(Error::Syntax { code: ErrorCode::InvalidEscapeSequence, .. }),
+        ), "{:?} should be an invalid escape", hjson);
+    }
+}
@@ -37,3 +37,12 @@ fn test_choke_on_trailing_chars() {
     assert!(deser_hjson::from_str::<f64>("1e-3 e").is_err());
 }
 
+/// a comment may end the document without a newline
+#[test]
+fn test_accept_trailing_comment_without_newline() {
+    #[derive(Debug, Deserialize)]
+    struct T {}
+    deser_hjson::from_str::<T>("{} // the end").unwrap();
+    deser_hjson::from_str::<i32>("1 # one").unwrap();
+    deser_hjson::from_str::<Vec<u8>>("[1, 2] /* two */ // items").unwrap();
+}
@@ -0,0 +1,126 @@
+use {
+    deser_hjson::*,
+};
+
+#[macro_use] mod common;
+
+static HJSON: &str = r#"
+// the numbers are kept as written
+zeta: 1.50
+alpha: -0
+big: 123456789012345678901234567890
+exp: 6.02E+23
+# strings
+text: hello "world"
+quoted: 'single \' quote'
+multi:
+    '''
+    line 1
+    line 2
+    '''
+empty: {}
+list: [
+    true
+    false
+    null
+    3 apples
+    []
+]
+"#;
+
+fn transcoded(hjson: &str) -> Result<String> {
+    let mut json = Vec::new();
+    transcode(hjson, &mut json)?;
+    Ok(String::from_utf8(json).unwrap())
+}
+
+fn transcoded_pretty(hjson: &str) -> Result<String> {
+    let mut json = Vec::new();
+    transcode_pretty(hjson, &mut json)?;
+    Ok(String::from_utf8(json).unwrap())
+}
+
+#[test]
+fn test_compact() {
+    assert_eq!(
+        transcoded(HJSON).unwrap(),
+        concat!(
+            r#"{"zeta":1.50,"alpha":-0,"big":123456789012345678901234567890,"exp":6.02E+23,"#,
+            r#""text":"hello \"world\"","quoted":"single ' quote","multi":"line 1\nline 2","#,
+            r#""empty":{},"list":[true,false,null,"3 apples",[]]}"#,
+        ),
+    );
+    assert_eq!(transcoded("").unwrap(), "{}");
+    assert_eq!(transcoded("[]").unwrap(), "[]");
+    assert_eq!(transcoded("\"a\\tb\"").unwrap(), r#""a\tb""#);
+    assert_eq!(transcoded("-12.5e-3").unwrap(), "-12.5e-3");
+}
+
+#[test]
+fn test_pretty() {
+    assert_eq!(
+        transcoded_pretty("a: 1\nb: [\n  2\n  {c: null}\n]\nd: []").unwrap(),
+        r#"{
+  "a": 1,
+  "b": [
+    2,
+    {
+      "c": null
+    }
+  ],
+  "d": []
+}"#,
+    );
+}
+
+#[test]
+fn test_same_as_serde_json() {
+    let json = transcoded(HJSON).unwrap();
+    let from_json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let from_hjson: serde_json::Value = from_str(&HJSON.replace("123456789012345678901234567890", "1")).unwrap();
+    assert_eq!(from_json["text"], from_hjson["text"]);
+    assert_eq!(from_json["multi"], from_hjson["multi"]);
+    assert_eq!(from_json["list"], from_hjson["list"]);
+    if cfg!(not(feature = "arbitrary_precision")) { // or serde_json gets a map
+        assert_eq!(from_json["exp"], from_hjson["exp"]);
+    }
+}
+
+#[test]
+fn test_from_reader() {
+    let mut hjson = String::from("items: [\n");
+    for i in 0..10_000 {
+        hjson.push_str(&format!("  {{ id: {}, name: \"item {}\" }}\n", i, i));
+    }
+    hjson.push_str("]\n");
+    let mut deserializer = Deserializer::from_reader(hjson.as_bytes());
+    let mut json = Vec::new();
+    deserializer.transcode(&mut json).unwrap();
+    deserializer.check_all_consumed().unwrap();
+    assert_eq!(String::from_utf8(json).unwrap(), transcoded(&hjson).unwrap());
+}
+
+#[test]
+fn test_errors() {
+    // the errors are the ones of deserialization
+    for hjson in ["a: 1\nb: [1, 2\nc: 3", "a: {\n  b: [1, :]\n}", "a: 1\nb 2"] {
+        let err = transcoded(hjson).unwrap_err();
+        let expected = from_str::<Value>(hjson).unwrap_err();
+        assert_eq!(err.location(), expected.location());
+        assert_eq!(err.path(), expected.path());
+    }
+    let err = transcoded("a: {\n  b: [1, :]\n}").unwrap_err();
+    assert_eq!(err.path().unwrap().to_string(), "a.b[1]");
+    assert!(transcoded("[1] [2]").is_err());
+}
+
+#[test]
+fn test_options() {
+    let options = DeserializerOptions::strict_json()
+        .duplicate_keys(DuplicateKeys::Error);
+    let mut json = Vec::new();
+    let mut deserializer = Deserializer::with_options(r#"{"a": 1, "a": 2}"#, options.clone());
+    assert!(deserializer.transcode(&mut json).is_err());
+    let mut deserializer = Deserializer::with_options("a: 1", options);
+    assert!(deserializer.transcode(&mut json).is_err());
+}
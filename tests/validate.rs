@@ -0,0 +1,137 @@
+use {
+    deser_hjson::{*, ErrorCode::*},
+};
+
+#[macro_use] mod common;
+
+/// return the codes, locations and paths of the errors of the document
+fn errors(hjson: &str) -> Vec<(ErrorCode, usize, usize, String)> {
+    validate_str(hjson)
+        .into_iter()
+        .map(|e| match e {
+            Error::Syntax { code, line, col, path, .. } => (code, line, col, path.to_string()),
+            e => panic!("unexpected error: {:?}", e),
+        })
+        .collect()
+}
+
+#[test]
+fn test_valid_documents() {
+    for hjson in &[
+        "",
+        "a: 1",
+        "// only a comment",
+        "{}",
+        "[]",
+        "[1, 2, 3]",
+        "\"a string\"",
+        "42",
+        r#"
+        name: Alice
+        tags: [ "a", "b", 'c' ]
+        nested: { x: 1, y: -2.5e3, z: null }
+        text:
+            '''
+            multiline
+            '''
+        "#,
+    ] {
+        assert_eq!(errors(hjson), vec![], "in {:?}", hjson);
+    }
+}
+
+#[test]
+fn test_several_errors() {
+    let hjson = r#"
+name: Alice
+age 34
+location: "Menlo Park\q, CA"
+langs: [
+    Rust
+    ]
+    "C\u12G"
+]
+pets: {
+    cat: 1
+    : 2
+}
+height: 1.8
+"#;
+    assert_eq!(
+        errors(hjson),
+        vec![
+            (ExpectedMapColon, 3, 5, "age".to_string()),
+            (InvalidEscapeSequence, 4, 22, "location".to_string()),
+            (InvalidEscapeSequence, 8, 7, "".to_string()),
+            (UnexpectedChar, 9, 1, "".to_string()),
+            (UnexpectedChar, 12, 5, "pets".to_string()),
+        ],
+    );
+}
+
+#[test]
+fn test_errors_in_nested_values() {
+    let hjson = "{\n  b: {\n    c \"x\"\n    d: \"y\\q\"\n  }\n  e: [1, 2}\n}";
+    assert_eq!(
+        errors(hjson),
+        vec![
+            (ExpectedMapColon, 3, 7, "b.c".to_string()),
+            (InvalidEscapeSequence, 4, 10, "b.d".to_string()),
+            // the brace is taken as the end of the enclosing map
+            (ExpectedArrayEnd, 6, 11, "e".to_string()),
+            (TrailingCharacters, 7, 1, "".to_string()),
+        ],
+    );
+}
+
+#[test]
+fn test_unclosed_document() {
+    assert_eq!(
+        errors("{\n  a: [1, 2\n"),
+        vec![(Eof, 3, 1, "a".to_string())],
+    );
+    assert_eq!(
+        errors("{ a: 1 ] }"),
+        vec![(UnexpectedChar, 1, 8, "".to_string())],
+    );
+}
+
+#[test]
+fn test_first_error_is_the_one_of_from_str() {
+    let hjson = "a: 1\nb: [\n  x\n  'y\\z'\n]\nc 3\n";
+    let err = from_str::<Value>(hjson).unwrap_err();
+    let all = validate_str(hjson);
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].location(), err.location());
+    assert_eq!(all[0].path(), err.path());
+}
+
+/// `validate_str` and `from_str` must agree on the validity of the
+/// document and, for an invalid one, on the location of the first error
+#[test]
+fn test_same_result_as_from_str() {
+    let atoms = ["/*", "*/", "*", "/", "#", ",", "a", ":", "1", "\n", " ", "[", "]", "{", "}", "'"];
+    let mut inputs = vec![String::new()];
+    for _ in 0..3 {
+        let longer: Vec<String> = inputs
+            .iter()
+            .flat_map(|s| atoms.iter().map(move |atom| format!("{}{}", s, atom)))
+            .collect();
+        inputs.extend(longer);
+    }
+    inputs.extend([
+        "/*,", "/* a", "a: 1\n/* b", "[1] /*", "{a: /* 1}", "a: 1 /* c */\n", "/**/",
+    ].map(String::from));
+    for hjson in &inputs {
+        let read = from_str::<Value>(hjson);
+        let errors = validate_str(hjson);
+        match read {
+            Ok(_) => assert_eq!(errors.len(), 0, "{:?} is valid", hjson),
+            Err(e) => {
+                assert!(!errors.is_empty(), "{:?} is invalid: {}", hjson, e);
+                assert_eq!(errors[0].location(), e.location(), "in {:?}", hjson);
+            }
+        }
+    }
+    assert!(from_str::<Value>("/*,").is_err());
+}
@@ -0,0 +1,155 @@
+use {
+    deser_hjson::*,
+    serde::Deserialize,
+};
+
+#[macro_use] mod common;
+
+static HJSON: &str = r#"
+// a list of servers
+servers: [
+    {
+        name: alpha
+        port: 8080
+        ratio: 0.5
+        enabled: true
+    }
+    {
+        name: "beta"
+        port: -1
+        enabled: false
+        comment: null
+    }
+]
+zebra: last letter
+apple: first letter
+"#;
+
+#[test]
+fn test_value_types() {
+    let value: Value = from_str(HJSON).unwrap();
+    let servers = value["servers"].as_array().unwrap();
+    assert_eq!(servers.len(), 2);
+    assert_eq!(value["servers"][0]["name"], Value::from("alpha"));
+    assert_eq!(value["servers"][0]["port"].as_u64(), Some(8080));
+    assert_eq!(value["servers"][0]["ratio"].as_f64(), Some(0.5));
+    assert_eq!(value["servers"][0]["enabled"].as_bool(), Some(true));
+    assert_eq!(value["servers"][1]["name"].as_str(), Some("beta"));
+    assert_eq!(value["servers"][1]["port"].as_i64(), Some(-1));
+    assert_eq!(value["servers"][1]["port"].as_u64(), None);
+    assert!(value["servers"][1]["comment"].is_null());
+    assert!(value["servers"][1].get("comment").is_some());
+    assert!(value["servers"][1].get("missing").is_none());
+    assert!(value["servers"][5]["name"].is_null());
+    assert!(value["zebra"][0].is_null());
+}
+
+#[test]
+fn test_value_keeps_order() {
+    let value: Value = from_str(HJSON).unwrap();
+    let keys: Vec<&String> = value.as_map().unwrap().keys().collect();
+    assert_eq!(keys, vec!["servers", "zebra", "apple"]);
+    let keys: Vec<&String> = value["servers"][1].as_map().unwrap().keys().collect();
+    assert_eq!(keys, vec!["name", "port", "enabled", "comment"]);
+    // and this order survives a serialization
+    let hjson = to_string_pretty(&value).unwrap();
+    assert!(hjson.find("zebra").unwrap() < hjson.find("apple").unwrap());
+    let read: Value = from_str(&hjson).unwrap();
+    assert_eq!(read, value);
+}
+
+/// check the map operations, on a map big enough for linear lookups to be too slow
+#[test]
+fn test_map_operations() {
+    let mut map = Map::new();
+    for i in 0..100_000 {
+        assert_eq!(map.insert(format!("key {}", i), Value::from(i as u64)), None);
+    }
+    assert_eq!(map.len(), 100_000);
+    for i in 0..100_000 {
+        assert_eq!(map.get(&format!("key {}", i)).and_then(Value::as_u64), Some(i as u64));
+    }
+    assert_eq!(map.insert("key 3".to_string(), Value::from("three")), Some(Value::from(3u64)));
+    assert_eq!(map.remove("key 1"), Some(Value::from(1u64)));
+    assert_eq!(map.remove("key 1"), None);
+    assert!(!map.contains_key("key 1"));
+    *map.get_mut("key 99999").unwrap() = Value::Null;
+    assert!(map.get("key 99999").unwrap().is_null());
+    let keys: Vec<&String> = map.keys().take(3).collect();
+    assert_eq!(keys, vec!["key 0", "key 2", "key 3"]);
+    assert_eq!(map.get("key 3"), Some(&Value::from("three")));
+    assert_eq!(map.get("key 4"), Some(&Value::from(4u64)));
+    map.insert("key 1".to_string(), Value::Null);
+    assert_eq!(map.keys().last().unwrap(), "key 1");
+    assert_eq!(map.len(), 100_000);
+    let copy: Map = map.clone().into_iter().collect();
+    assert_eq!(copy, map);
+}
+
+#[test]
+fn test_value_into_typed() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Server {
+        name: String,
+        port: i32,
+        ratio: Option<f64>,
+        enabled: bool,
+        comment: Option<String>,
+    }
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        servers: Vec<Server>,
+        zebra: String,
+    }
+    let mut value: Value = from_str(HJSON).unwrap();
+    let servers = value.get_mut("servers").unwrap().take();
+    let servers: Vec<Server> = from_value(servers).unwrap();
+    assert_eq!(servers[0].name, "alpha");
+    assert_eq!(servers[0].ratio, Some(0.5));
+    assert_eq!(servers[1].ratio, None);
+    assert_eq!(servers[1].comment, None);
+    assert!(from_value::<Config>(value).is_err()); // servers is now null
+    let config: Config = from_value(from_str(HJSON).unwrap()).unwrap();
+    assert_eq!(config.servers, servers);
+    assert_eq!(config.zebra, "last letter");
+}
+
+/// a value which isn't a map is an error, not a panic, when a map is expected
+#[test]
+fn test_value_into_map() {
+    let map: Map = from_value(from_str(HJSON).unwrap()).unwrap();
+    assert!(map.contains_key("servers"));
+    let err = from_value::<Map>(Value::Array(vec![])).unwrap_err();
+    assert!(err.to_string().contains("invalid type: sequence, expected a map"));
+    let err = from_value::<Map>(Value::String("a".to_string())).unwrap_err();
+    assert!(err.to_string().contains(r#"invalid type: string \"a\", expected a map"#));
+    assert!(from_value::<Map>(Value::Null).is_err());
+    assert!(from_str::<Map>("[1, 2]").is_err());
+}
+
+#[test]
+fn test_value_into_enum() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Point(i32, i32),
+        Rect { width: u16, height: u16 },
+    }
+    let value: Value = from_str(r#"[
+        Empty
+        { Circle: 2.5 }
+        { Point: [-1, 2] }
+        { Rect: { width: 3, height: 4 } }
+    ]"#).unwrap();
+    let shapes: Vec<Shape> = from_value(value).unwrap();
+    assert_eq!(
+        shapes,
+        vec![
+            Shape::Empty,
+            Shape::Circle(2.5),
+            Shape::Point(-1, 2),
+            Shape::Rect { width: 3, height: 4 },
+        ],
+    );
+}